@@ -1,17 +1,46 @@
-use crate::{calendar::CalendarInfo, editor::write_to_file};
+use crate::attachments::{self, Selection};
+use crate::bookmarks::{self, Bookmarks, Picker};
+use crate::config::{self, Config};
+use crate::dateexpr;
+use crate::glyphs::{self, Glyphs};
+use crate::highlight::Viewport;
+use crate::history::{self, History, Source};
+use crate::journal::{self, JournalIndex};
+use crate::links;
+use crate::onthisday;
+use crate::palette::{self, ColorSupport};
+use crate::prompt::Prompt;
+use crate::revlog;
+use crate::theme::{self, Theme};
+use crate::tracker::{Overlay, Tracker};
+use crate::vault::{self, Vault};
+use crate::viewer::Viewer;
+use crate::{
+    calendar::{CalendarInfo, CalendarView},
+    editor::write_to_file,
+};
 
 use slog::Logger;
 use slog::{o, Drain};
 use slog_async::Async;
 use slog_term::{FullFormat, PlainSyncDecorator};
 
-use std::fmt::Display;
 use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{path::PathBuf, str::FromStr};
+use std::time::{Duration, Instant};
+use tempfile::TempPath;
 use time::{Date, OffsetDateTime};
 use tui_textarea::TextArea;
 
+/// Returns the local date, or the UTC one when the local offset is unknown.
+fn today() -> Date {
+    OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .date()
+}
+
 /// Application state holding various runtime properties.
 pub struct AppState<'a> {
     pub mode: Mode,
@@ -20,9 +49,52 @@ pub struct AppState<'a> {
     pub initialized: bool,
     pub holiday_info: Option<CalendarInfo>,
     pub editor: TextArea<'a>,
+    pub viewport: Viewport,
+    pub preview: bool,
+    /// Whether the panel of entries written on the same day in earlier years is shown.
+    pub on_this_day: bool,
+    /// Whether the panel of entries linking to the selected date is shown.
+    pub backlinks: bool,
+    /// Whether the keys of the current screen are listed over it.
+    pub help: bool,
+    /// An entry open read-only: in VIEWER mode, or over the calendar or editor.
+    pub viewer: Option<Viewer>,
     pub editor_text: String,
     pub entries_dir: PathBuf,
     pub logger: Arc<Logger>,
+    pub tracker: Tracker,
+    pub overlay: Overlay,
+    pub calendar_view: CalendarView,
+    pub index: Option<JournalIndex>,
+    pub config: Config,
+    pub theme: Theme,
+    pub themes: Vec<Theme>,
+    pub color_support: ColorSupport,
+    pub glyphs: &'static Glyphs,
+    /// Key of an encrypted journal once it has been unlocked.
+    pub vault: Option<Vault>,
+    /// Text input shown over the interface: the lock screen's, one of the calendar's, or the
+    /// editor's for attaching a file.
+    pub prompt: Option<Prompt>,
+    /// What the prompt is asking for, in CALENDAR mode.
+    pub calendar_prompt: CalendarPrompt,
+    /// The bookmark list, while it is open over the calendar.
+    pub bookmark_picker: Option<Picker>,
+    /// Position in the attachments panel, while it takes the keys.
+    pub attachment_selection: Option<Selection>,
+    /// Decrypted copies of an encrypted journal's attachments opened since it was last unlocked,
+    /// removed when dropped.
+    pub decrypted_attachments: Vec<TempPath>,
+    pub history: Option<History>,
+    /// Entry older than `lock_after_days` that was unlocked for the current edit.
+    pub unlocked_entry: Option<Date>,
+    /// Entry the editor opened as editable. It stays editable until the editor closes, even when
+    /// it grows older than `lock_after_days` in the meantime.
+    pub editing_entry: Option<Date>,
+    /// Mode to return to once the lock screen is dismissed.
+    pub resume: Mode,
+    /// When the last key or mouse input arrived, for the idle lock.
+    pub last_input: Instant,
 }
 
 /// Enum representing the different modes of operation within the application.
@@ -31,6 +103,21 @@ pub enum Mode {
     CALENDAR,
     EDITOR,
     SORT,
+    LOCKED,
+    HISTORY,
+    VIEWER,
+}
+
+/// What a prompt opened from the calendar is asking for.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum CalendarPrompt {
+    /// A date to jump to.
+    #[default]
+    GoTo,
+    /// A name for a bookmark on the selected date.
+    Bookmark,
+    /// Confirmation that a locked entry should be edited.
+    UnlockEntry,
 }
 
 impl std::fmt::Display for Mode {
@@ -39,25 +126,64 @@ impl std::fmt::Display for Mode {
             Mode::CALENDAR => write!(f, "CALENDAR"),
             Mode::EDITOR => write!(f, "EDITOR"),
             Mode::SORT => write!(f, "SORT"),
+            Mode::LOCKED => write!(f, "LOCKED"),
+            Mode::HISTORY => write!(f, "HISTORY"),
+            Mode::VIEWER => write!(f, "VIEWER"),
         }
     }
 }
 
+impl Default for AppState<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppState<'_> {
     /// Constructs a new `AppState` with default values.
     pub fn new() -> Self {
         let mut app = AppState {
             mode: Mode::CALENDAR,
-            selected_date: OffsetDateTime::now_local().unwrap().date(),
+            selected_date: today(),
             quit_flag: false,
             initialized: false,
             holiday_info: None,
             editor: TextArea::default(),
+            viewport: Viewport::default(),
+            preview: false,
+            on_this_day: false,
+            backlinks: false,
+            help: false,
+            viewer: None,
             editor_text: String::from(""),
-            entries_dir: PathBuf::from_str("entries").unwrap(),
+            entries_dir: PathBuf::from(journal::ENTRIES_DIR),
             logger: Arc::new(Logger::root(slog::Discard, slog::o!())), // Placeholder logger
+            tracker: Tracker::default(),
+            overlay: Overlay::default(),
+            calendar_view: CalendarView::default(),
+            index: None,
+            config: Config::default(),
+            theme: Theme::default(),
+            themes: theme::bundled(),
+            color_support: ColorSupport::TrueColor,
+            glyphs: &glyphs::UNICODE,
+            vault: None,
+            prompt: None,
+            calendar_prompt: CalendarPrompt::default(),
+            bookmark_picker: None,
+            attachment_selection: None,
+            decrypted_attachments: Vec::new(),
+            history: None,
+            unlocked_entry: None,
+            editing_entry: None,
+            resume: Mode::CALENDAR,
+            last_input: Instant::now(),
         };
         app.initialize_logging();
+        app.load_config();
+        if vault::exists(&app.entries_dir) {
+            app.lock();
+        }
         app
     }
 
@@ -70,17 +196,10 @@ impl AppState<'_> {
         let log_path = "app.log";
         self.editor_text = self.editor.lines().concat();
         // try to remove the existing log file
-        match fs::remove_file(log_path) {
-            Err(_) => {} // Ignore errors if the file does not exist
-            _ => {}      // Continue if the file was successfully removed
-        };
+        // ignore errors if the file does not exist
+        let _ = fs::remove_file(log_path);
         // logfile
-        match OpenOptions::new()
-            .create(true)
-            .write(true)
-            .append(true)
-            .open(log_path)
-        {
+        match OpenOptions::new().create(true).append(true).open(log_path) {
             Ok(logfile) => {
                 // instantiate logging
                 let decorator = PlainSyncDecorator::new(logfile);
@@ -93,24 +212,639 @@ impl AppState<'_> {
         };
     }
 
+    /// Reads `daydream.toml` and the user themes it points to, selecting the configured theme.
+    pub fn load_config(&mut self) {
+        match config::load(Path::new(config::CONFIG_PATH)) {
+            Ok(config) => self.config = config,
+            Err(e) => slog::error!(self.logger, "Failed to read config: {}", e),
+        }
+        let (themes, errors) = theme::load_all(&self.config.themes_dir);
+        for (path, e) in errors {
+            slog::error!(self.logger, "Failed to load theme"; "path" => path, "error" => %e);
+        }
+        self.themes = themes;
+        self.on_this_day = self.config.on_this_day;
+        self.color_support = self.config.color.unwrap_or_else(palette::detect);
+        slog::info!(self.logger, "Colour support"; "support" => %self.color_support);
+        let ascii = self.config.ascii.unwrap_or_else(glyphs::detect_ascii);
+        self.glyphs = glyphs::for_mode(ascii);
+        slog::info!(self.logger, "Glyphs"; "ascii" => ascii);
+        let name = self.config.theme.clone().unwrap_or(self.theme.name.clone());
+        match self.themes.iter().position(|theme| theme.name == name) {
+            Some(i) => self.set_theme(i),
+            None => {
+                slog::error!(self.logger, "Unknown theme"; "theme" => name);
+                self.set_theme(0);
+            }
+        }
+    }
+
+    /// Switches to the theme at `index` in `themes`, adapted to the terminal's colours.
+    pub fn set_theme(&mut self, index: usize) {
+        if let Some(theme) = self.themes.get(index) {
+            self.theme = theme.degraded(self.color_support);
+        }
+    }
+
+    /// Switches to the theme after the current one, wrapping around.
+    pub fn next_theme(&mut self) {
+        let position = self
+            .themes
+            .iter()
+            .position(|theme| theme.name == self.theme.name)
+            .map_or(0, |i| (i + 1) % self.themes.len());
+        self.set_theme(position);
+        slog::info!(self.logger, "Theme Changed"; "theme" => &self.theme.name);
+    }
+
     /// Resets the application state to default values.
     pub fn reset(&mut self) {
         self.mode = Mode::CALENDAR;
-        self.selected_date = OffsetDateTime::now_local().unwrap().date();
+        self.selected_date = today();
         self.quit_flag = false;
         self.initialized = false;
         self.editor = TextArea::default();
+        self.viewport = Viewport::default();
+        self.tracker = Tracker::default();
+        self.unlocked_entry = None;
+        self.editing_entry = None;
+        self.attachment_selection = None;
     }
 
     /// Saves the current state of the editor to a file and resets the application state.
     pub fn save(&mut self) {
+        let date = self.selected_date;
+        if self.is_sealed(date) {
+            slog::warn!(self.logger, "Refused to save locked entry"; "date" => %date);
+            self.reset();
+            return;
+        }
+        let message = if self.unlocked_entry == Some(date) {
+            format!("Edit locked entry {}", date)
+        } else {
+            format!("Update {}", date)
+        };
         match write_to_file(self) {
-            Ok(_) => {}
+            Ok(_) => self.record_version(date, &self.editor_text, &message),
             Err(e) => println!("Error writing to file: {:?}", e),
         }
+        if self.tracker.unreadable {
+            // writing would replace, or remove, the sidecar that could not be read
+            slog::warn!(self.logger, "Left unreadable entry metadata as it was"; "date" => %date);
+        } else if let Err(e) = journal::write_meta(
+            &self.entries_dir,
+            self.selected_date,
+            &self.tracker.meta,
+            self.vault.as_ref(),
+        ) {
+            slog::error!(self.logger, "Failed to write entry metadata"; "error" => %e);
+        }
+        self.index = None;
         self.reset();
     }
 
+    /// Returns the journal index, scanning the entries directory if it is stale.
+    pub fn journal_index(&mut self) -> &JournalIndex {
+        let entries_dir = &self.entries_dir;
+        let vault = self.vault.as_ref();
+        self.index
+            .get_or_insert_with(|| journal::build_index(entries_dir, vault))
+    }
+
+    /// Returns the preview of the entry on a date, reading it the first time it is asked for.
+    pub fn entry_preview(&mut self, date: Date) -> Option<&str> {
+        let entries_dir = &self.entries_dir;
+        let vault = self.vault.as_ref();
+        let index = self
+            .index
+            .get_or_insert_with(|| journal::build_index(entries_dir, vault));
+        if !index.dates.contains(&date) {
+            return None;
+        }
+        let preview = index.previews.entry(date).or_insert_with(|| {
+            match journal::read_entry(entries_dir, date, vault) {
+                Ok(Some(text)) => journal::preview(&text),
+                _ => String::new(),
+            }
+        });
+        Some(preview)
+    }
+
+    /// Returns the entries linking to `date`, newest first, reading every entry the first time.
+    pub fn links_to(&mut self, date: Date) -> Vec<Date> {
+        let entries_dir = &self.entries_dir;
+        let vault = self.vault.as_ref();
+        let index = self
+            .index
+            .get_or_insert_with(|| journal::build_index(entries_dir, vault));
+        let backlinks = index
+            .backlinks
+            .get_or_insert_with(|| links::backlinks(entries_dir, &index.dates, vault));
+        backlinks
+            .get(&date)
+            .map(|sources| sources.iter().rev().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the files attached to `date`, listing its folder the first time.
+    pub fn attachment_names(&mut self, date: Date) -> Vec<String> {
+        let entries_dir = &self.entries_dir;
+        let vault = self.vault.as_ref();
+        let index = self
+            .index
+            .get_or_insert_with(|| journal::build_index(entries_dir, vault));
+        index
+            .attachments
+            .entry(date)
+            .or_insert_with(|| attachments::list(entries_dir, date))
+            .clone()
+    }
+
+    /// Returns true when any panel is shown next to the calendar, editor or viewer.
+    pub fn side_panels_shown(&mut self) -> bool {
+        self.on_this_day || self.backlinks || !self.attachment_names(self.selected_date).is_empty()
+    }
+
+    /// Hands the keys to the attachments panel, if the selected date has any.
+    pub fn select_attachment(&mut self) {
+        if !self.attachment_names(self.selected_date).is_empty() {
+            self.attachment_selection = Some(Selection::default());
+        }
+    }
+
+    /// Opens the `i`th attachment of the selected date with the system's program for it.
+    pub fn open_attachment(&mut self, i: usize) {
+        let date = self.selected_date;
+        let Some(name) = self.attachment_names(date).into_iter().nth(i) else {
+            return;
+        };
+        match attachments::open_attached(&self.entries_dir, date, &name, self.vault.as_ref()) {
+            Ok(decrypted) => {
+                self.decrypted_attachments.extend(decrypted);
+                slog::info!(self.logger, "Opened Attachment"; "date" => %date, "name" => &name)
+            }
+            Err(e) => {
+                slog::error!(self.logger, "Failed to open attachment"; "date" => %date, "name" => &name, "error" => %e)
+            }
+        }
+    }
+
+    /// Opens the prompt for the path of a file to attach to the entry being edited.
+    pub fn open_attach_prompt(&mut self) {
+        self.prompt = Some(Prompt::new("attach a file (path)", false));
+    }
+
+    /// Copies the file at the typed path into the attachments of the entry being edited and
+    /// links it at the cursor.
+    pub fn attach(&mut self, text: &str) {
+        let date = self.selected_date;
+        let source = attachments::input_path(text);
+        match attachments::attach(&self.entries_dir, date, &source, self.vault.as_ref()) {
+            Ok(name) => {
+                self.prompt = None;
+                let label = source.file_name().map_or_else(
+                    || name.clone(),
+                    |label| label.to_string_lossy().into_owned(),
+                );
+                self.editor
+                    .insert_str(attachments::markdown_link(date, &name, &label));
+                if let Some(index) = self.index.as_mut() {
+                    index.attachments.remove(&date);
+                }
+                slog::info!(self.logger, "Attached File"; "date" => %date, "name" => name);
+            }
+            Err(e) => {
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.fail(match e.kind() {
+                        ErrorKind::NotFound => "no file at that path".to_string(),
+                        kind => format!("could not attach it: {}", kind),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Forgets the key of an encrypted journal and shows the lock screen.
+    pub fn lock(&mut self) {
+        // the viewer holds the entry's text, so it is closed rather than resumed
+        if self.mode == Mode::VIEWER {
+            self.resume = Mode::CALENDAR;
+        } else if self.mode != Mode::LOCKED {
+            self.resume = self.mode;
+        }
+        self.vault = None;
+        self.help = false;
+        self.decrypted_attachments.clear();
+        self.index = None;
+        self.bookmark_picker = None;
+        self.attachment_selection = None;
+        self.viewer = None;
+        self.mode = Mode::LOCKED;
+        self.prompt = Some(if vault::exists(&self.entries_dir) {
+            Prompt::new("passphrase", true)
+        } else if self.config.lock_pin.is_some() {
+            Prompt::new("PIN", true)
+        } else {
+            Prompt::new("press Enter to resume", false)
+        });
+        slog::info!(self.logger, "Journal Locked");
+    }
+
+    /// Saves the entry being edited, then locks. Unlocking reopens it.
+    pub fn lock_screen(&mut self) {
+        if self.mode == Mode::EDITOR {
+            self.save_in_place();
+        }
+        self.lock();
+    }
+
+    /// Saves the entry being edited and stays on it; the editor rereads it when shown again.
+    pub fn save_in_place(&mut self) {
+        let date = self.selected_date;
+        let (unlocked, editing) = (self.unlocked_entry, self.editing_entry);
+        self.save();
+        self.selected_date = date;
+        self.unlocked_entry = unlocked;
+        self.editing_entry = editing;
+        self.mode = Mode::EDITOR;
+    }
+
+    /// Records key or mouse input, postponing the idle lock.
+    pub fn touch(&mut self) {
+        self.last_input = Instant::now();
+    }
+
+    /// Locks the screen when there has been no input for `idle_lock_minutes`.
+    pub fn check_idle(&mut self) {
+        let Some(minutes) = self.config.idle_lock_minutes.filter(|minutes| *minutes > 0) else {
+            return;
+        };
+        if self.mode != Mode::LOCKED
+            && self.last_input.elapsed() >= Duration::from_secs(minutes * 60)
+        {
+            slog::info!(self.logger, "Idle Timeout"; "minutes" => minutes);
+            self.lock_screen();
+        }
+    }
+
+    /// Tries to unlock with a passphrase or PIN, showing the error in the prompt when it fails.
+    pub fn unlock(&mut self, secret: &str) {
+        let result = if vault::exists(&self.entries_dir) {
+            Vault::unlock(&self.entries_dir, secret).map(Some)
+        } else {
+            match &self.config.lock_pin {
+                Some(pin) if pin != secret => {
+                    Err(io::Error::new(ErrorKind::PermissionDenied, "wrong PIN"))
+                }
+                _ => Ok(None),
+            }
+        };
+        match result {
+            Ok(vault) => {
+                self.vault = vault;
+                self.prompt = None;
+                self.mode = self.resume;
+                self.resume = Mode::CALENDAR;
+                self.touch();
+                slog::info!(self.logger, "Journal Unlocked");
+            }
+            Err(e) => {
+                slog::error!(self.logger, "Failed to unlock journal"; "error" => %e);
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.fail(e);
+                }
+            }
+        }
+    }
+
+    /// Opens the prompt for jumping to a date.
+    pub fn open_goto(&mut self) {
+        self.calendar_prompt = CalendarPrompt::GoTo;
+        self.prompt = Some(Prompt::new("go to date", false));
+    }
+
+    /// Selects the date `text` describes and closes the prompt, or keeps it open with the error.
+    pub fn go_to(&mut self, text: &str) {
+        match dateexpr::parse(text, today()) {
+            Ok(date) => {
+                self.selected_date = date;
+                self.prompt = None;
+                slog::info!(self.logger, "Date Selected"; "date" => %date);
+            }
+            Err(e) => {
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.input = text.to_string();
+                    prompt.fail(e);
+                }
+            }
+        }
+    }
+
+    /// Selects today.
+    pub fn go_to_today(&mut self) {
+        self.selected_date = today();
+    }
+
+    /// Opens the selected date: entries of past days in the viewer, anything else in the editor.
+    pub fn open_selected(&mut self) {
+        let date = self.selected_date;
+        if date < today() && self.journal_index().dates.contains(&date) {
+            match journal::read_entry(&self.entries_dir, date, self.vault.as_ref()) {
+                Ok(Some(text)) => {
+                    self.viewer = Some(Viewer::new(date, text));
+                    self.mode = Mode::VIEWER;
+                    slog::info!(self.logger, "Mode Changed"; "mode" => %self.mode);
+                    return;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    slog::error!(self.logger, "Failed to read entry"; "date" => %date, "error" => %e);
+                    return;
+                }
+            }
+        }
+        self.edit_selected();
+    }
+
+    /// Returns true for entries older than `lock_after_days`, which are only edited once unlocked.
+    pub fn entry_locked(&mut self, date: Date) -> bool {
+        let Some(days) = self.config.lock_after_days else {
+            return false;
+        };
+        let oldest_open = today()
+            .checked_sub(time::Duration::days(days.into()))
+            .unwrap_or(Date::MIN);
+        date < oldest_open && self.journal_index().dates.contains(&date)
+    }
+
+    /// Returns true when the entry on `date` is locked and has not been unlocked for editing.
+    /// The lock is decided when the editor opens, so an open entry never locks under the cursor.
+    pub fn is_sealed(&mut self, date: Date) -> bool {
+        self.unlocked_entry != Some(date)
+            && self.editing_entry != Some(date)
+            && self.entry_locked(date)
+    }
+
+    /// Opens the selected date in the editor, or asks for it to be unlocked first.
+    pub fn edit_selected(&mut self) {
+        let date = self.selected_date;
+        if self.is_sealed(date) {
+            self.mode = Mode::CALENDAR;
+            self.calendar_prompt = CalendarPrompt::UnlockEntry;
+            self.prompt = Some(Prompt::new(
+                &format!("{} is locked, type `unlock` to edit", date),
+                false,
+            ));
+            return;
+        }
+        self.editing_entry = Some(date);
+        self.mode = Mode::EDITOR;
+        slog::info!(self.logger, "Mode Changed"; "mode" => %self.mode);
+    }
+
+    /// Unlocks the selected entry for editing when `text` is `unlock`, recording that it was.
+    pub fn unlock_entry(&mut self, text: &str) {
+        if text.trim() != "unlock" {
+            if let Some(prompt) = self.prompt.as_mut() {
+                prompt.fail("type `unlock`, or `Esc` to leave it as it was");
+            }
+            return;
+        }
+        let date = self.selected_date;
+        self.prompt = None;
+        self.unlocked_entry = Some(date);
+        slog::warn!(self.logger, "Locked Entry Unlocked"; "date" => %date);
+        self.edit_selected();
+    }
+
+    /// Closes the viewer, switching to the editor on its entry when `edit` is set.
+    pub fn close_viewer(&mut self, edit: bool) {
+        let Some(viewer) = self.viewer.take() else {
+            return;
+        };
+        if edit {
+            self.selected_date = viewer.date;
+            self.initialized = false;
+            self.edit_selected();
+        } else if self.mode == Mode::VIEWER {
+            self.mode = Mode::CALENDAR;
+            slog::info!(self.logger, "Mode Changed"; "mode" => %self.mode);
+        }
+    }
+
+    /// Returns the entries listed in the side panels shown, in the order they are numbered.
+    pub fn listed_entries(&mut self) -> Vec<Date> {
+        let date = self.selected_date;
+        let mut listed = Vec::new();
+        if self.on_this_day {
+            let memories = onthisday::memories(date, &self.journal_index().dates);
+            listed.extend(memories.iter().map(|memory| memory.date));
+        }
+        if self.backlinks {
+            listed.extend(self.links_to(date));
+        }
+        listed
+    }
+
+    /// Opens the `n`th entry of the side panels, counting from 1, read-only. In VIEWER mode the
+    /// panels then follow the entry opened.
+    pub fn open_listed(&mut self, n: usize) {
+        let listed = self.listed_entries();
+        let Some(date) = n.checked_sub(1).and_then(|i| listed.get(i)).copied() else {
+            return;
+        };
+        match journal::read_entry(&self.entries_dir, date, self.vault.as_ref()) {
+            Ok(text) => {
+                self.viewer = Some(Viewer::new(date, text.unwrap_or_default()));
+                if self.mode == Mode::VIEWER {
+                    self.selected_date = date;
+                }
+                slog::info!(self.logger, "Viewing Entry"; "date" => %date);
+            }
+            Err(e) => {
+                slog::error!(self.logger, "Failed to read entry"; "date" => %date, "error" => %e)
+            }
+        }
+    }
+
+    /// Saves the entry being edited and opens the date of the `[[link]]` under the cursor.
+    pub fn follow_link(&mut self) {
+        let (row, column) = self.editor.cursor();
+        let Some(target) = links::link_at(self.editor.lines(), row, column, self.selected_date)
+        else {
+            return;
+        };
+        self.save();
+        self.selected_date = target;
+        slog::info!(self.logger, "Followed Link"; "date" => %target);
+        self.open_selected();
+    }
+
+    /// Opens the prompt for naming a bookmark on the selected date, filled in with its name.
+    pub fn open_bookmark_prompt(&mut self) {
+        let date = self.selected_date;
+        let mut prompt = Prompt::new(&format!("bookmark {} (empty removes)", date), false);
+        if let Some(name) = self.journal_index().bookmarks.get(&date) {
+            prompt.input = name.clone();
+        }
+        self.calendar_prompt = CalendarPrompt::Bookmark;
+        self.prompt = Some(prompt);
+    }
+
+    /// Applies a change to the bookmarks file, reread first so that a file the index could not
+    /// read is never overwritten.
+    fn change_bookmarks(&mut self, change: impl FnOnce(&mut Bookmarks)) -> io::Result<()> {
+        let vault = self.vault.as_ref();
+        let mut bookmarks = bookmarks::read(&self.entries_dir, vault)?;
+        change(&mut bookmarks);
+        bookmarks::write(&self.entries_dir, &bookmarks, vault)?;
+        if let Some(index) = self.index.as_mut() {
+            index.bookmarks = bookmarks;
+        }
+        Ok(())
+    }
+
+    /// Names the bookmark on the selected date and closes the prompt, or removes the bookmark
+    /// when the name is empty.
+    pub fn set_bookmark(&mut self, name: &str) {
+        let date = self.selected_date;
+        let name = name.trim().to_string();
+        let result = self.change_bookmarks(|bookmarks| {
+            if name.is_empty() {
+                bookmarks.remove(&date);
+            } else {
+                bookmarks.insert(date, name.clone());
+            }
+        });
+        match result {
+            Ok(()) => {
+                self.prompt = None;
+                slog::info!(self.logger, "Bookmark Changed"; "date" => %date, "name" => &name);
+            }
+            Err(e) => {
+                slog::error!(self.logger, "Failed to save bookmarks"; "error" => %e);
+                if let Some(prompt) = self.prompt.as_mut() {
+                    prompt.input = name;
+                    prompt.fail(e);
+                }
+            }
+        }
+    }
+
+    /// Opens the bookmark list on the bookmark nearest the selected date.
+    pub fn open_bookmarks(&mut self) {
+        let date = self.selected_date;
+        self.bookmark_picker = Some(Picker::new(&self.journal_index().bookmarks, date));
+    }
+
+    /// Removes the bookmark on a date from the list.
+    pub fn remove_bookmark(&mut self, date: Date) {
+        match self.change_bookmarks(|bookmarks| {
+            bookmarks.remove(&date);
+        }) {
+            Ok(()) => {
+                let count = self.journal_index().bookmarks.len();
+                if let Some(picker) = self.bookmark_picker.as_mut() {
+                    picker.selected = picker.selected.min(count.saturating_sub(1));
+                }
+                slog::info!(self.logger, "Bookmark Removed"; "date" => %date);
+            }
+            Err(e) => slog::error!(self.logger, "Failed to save bookmarks"; "error" => %e),
+        }
+    }
+
+    /// Keeps a version of a saved entry in git and/or its revision log, as configured.
+    fn record_version(&self, date: Date, text: &str, message: &str) {
+        if self.config.history {
+            match history::commit(&self.entries_dir, message) {
+                Ok(committed) => {
+                    slog::info!(self.logger, "History Commit"; "committed" => committed)
+                }
+                Err(e) => slog::error!(self.logger, "Failed to commit history"; "error" => %e),
+            }
+        }
+        if self.config.revisions {
+            let now = OffsetDateTime::now_utc().unix_timestamp();
+            match revlog::append(&self.entries_dir, date, text, now, self.vault.as_ref()) {
+                Ok(added) => slog::info!(self.logger, "Revision Saved"; "added" => added),
+                Err(e) => slog::error!(self.logger, "Failed to save revision"; "error" => %e),
+            }
+        }
+    }
+
+    /// Shows the saved versions of the selected date's entry, saving it first when it is open in
+    /// the editor.
+    pub fn open_history(&mut self) {
+        let from_editor = self.mode == Mode::EDITOR
+            || self
+                .history
+                .as_ref()
+                .is_some_and(|history| history.from_editor);
+        if self.mode == Mode::EDITOR {
+            self.save_in_place();
+        }
+        let source = if self.config.revisions {
+            Source::Revisions
+        } else {
+            Source::Git
+        };
+        match History::open(
+            &self.entries_dir,
+            self.selected_date,
+            source,
+            self.vault.as_ref(),
+        ) {
+            Ok(mut history) => {
+                history.from_editor = from_editor;
+                self.history = Some(history);
+                self.mode = Mode::HISTORY;
+                slog::info!(self.logger, "Mode Changed"; "mode" => %self.mode);
+            }
+            Err(e) => slog::error!(self.logger, "Failed to read history"; "error" => %e),
+        }
+    }
+
+    /// Leaves the history view for the screen it was opened from.
+    pub fn close_history(&mut self) {
+        let from_editor = self
+            .history
+            .take()
+            .is_some_and(|history| history.from_editor);
+        self.mode = if from_editor {
+            self.initialized = false;
+            Mode::EDITOR
+        } else {
+            Mode::CALENDAR
+        };
+        slog::info!(self.logger, "Mode Changed"; "mode" => %self.mode);
+    }
+
+    /// Replaces the entry with the version selected in the history view.
+    pub fn restore_version(&mut self) {
+        let Some(date) = self.history.as_ref().map(|history| history.date) else {
+            return;
+        };
+        if self.is_sealed(date) {
+            slog::warn!(self.logger, "Refused to restore locked entry"; "date" => %date);
+            return;
+        }
+        let Some(history) = &self.history else {
+            return;
+        };
+        let (Some(text), Some(version)) = (&history.version_text, history.version()) else {
+            return;
+        };
+        let date = history.date;
+        if let Err(e) = journal::write_entry(&self.entries_dir, date, text, self.vault.as_ref()) {
+            slog::error!(self.logger, "Failed to restore entry"; "error" => %e);
+            return;
+        }
+        slog::info!(self.logger, "Entry Restored"; "date" => %date, "version" => &version.id);
+        self.record_version(date, text, &format!("Restore {} to {}", date, version.time));
+        self.index = None;
+        self.open_history();
+    }
+
     /// Sets the quit flag to true, indicating that the application should exit.
     pub fn quit(&mut self) {
         self.quit_flag = true;
@@ -0,0 +1,396 @@
+use crate::journal;
+use crate::vault::VAULT_FILE;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Component, Path, PathBuf};
+use time::Date;
+
+/// Name of the archive member listing the SHA-256 of every other member.
+pub const MANIFEST: &str = "MANIFEST.sha256";
+
+/// Directories inside the journal that are never archived: git's own data and trashed files.
+const SKIPPED_DIRS: [&str; 2] = [".git", ".trash"];
+
+/// Folder, next to the journal, holding a numbered folder for each restore that kept archived
+/// files alongside existing ones.
+pub const RESTORED_DIR: &str = ".restored";
+
+/// What to do with archived files whose date, or path, already has different content.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Merge {
+    /// Refuse to restore anything.
+    Fail,
+    /// Keep the existing files and leave the archived ones out.
+    Skip,
+    /// Restore the archived files, with their own names, into a folder of `.restored`.
+    Rename,
+}
+
+/// What a restore did.
+#[derive(Default, Debug)]
+pub struct Report {
+    pub written: Vec<PathBuf>,
+    pub unchanged: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Returns the path with `/` separators, as stored in the archive.
+fn archive_name(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Adds every file under `dir` to `files`, as paths relative to `root`.
+fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let read_dir = match fs::read_dir(root.join(dir)) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in read_dir {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = dir.join(&name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() && !SKIPPED_DIRS.contains(&name.as_str()) {
+            walk(root, &path, files)?;
+        } else if file_type.is_file() && !name.ends_with(".tmp") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Lists the files that make up the journal: everything in the entries directory, the
+/// configuration file and the user themes. Paths are relative to `root`.
+pub fn journal_files(
+    root: &Path,
+    entries_dir: &Path,
+    config_path: &Path,
+    themes_dir: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    walk(root, entries_dir, &mut files)?;
+    walk(root, themes_dir, &mut files)?;
+    if root.join(config_path).is_file() {
+        files.push(config_path.to_path_buf());
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Writes a gzip-compressed tar archive of `files`, preceded by their checksums.
+pub fn create(archive: &Path, root: &Path, files: &[PathBuf]) -> io::Result<()> {
+    let mut contents = Vec::new();
+    let mut manifest = String::new();
+    for path in files {
+        let data = fs::read(root.join(path))?;
+        manifest.push_str(&format!("{}  {}\n", sha256(&data), archive_name(path)));
+        contents.push((archive_name(path), data));
+    }
+
+    let mut temporary = archive.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    let encoder = GzEncoder::new(File::create(&temporary)?, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    let mut append = |name: &str, data: &[u8]| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_cksum();
+        builder.append_data(&mut header, name, data)
+    };
+    append(MANIFEST, manifest.as_bytes())?;
+    for (name, data) in &contents {
+        append(name, data)?;
+    }
+    builder.into_inner()?.finish()?.sync_all()?;
+    fs::rename(&temporary, archive)
+}
+
+/// Rejects archive paths that could escape the directory they are restored into.
+fn safe_path(name: &str) -> io::Result<PathBuf> {
+    let path = PathBuf::from(name);
+    if name.is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(invalid(format!("unsafe path `{}` in archive", name)));
+    }
+    Ok(path)
+}
+
+/// Reads an archive, checking every file against the manifest.
+pub fn read(archive: &Path) -> io::Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+    let mut manifest = None;
+    let mut files = BTreeMap::new();
+    for member in tar.entries()? {
+        let mut member = member?;
+        if !member.header().entry_type().is_file() {
+            continue;
+        }
+        let name = member.path()?.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        member.read_to_end(&mut data)?;
+        if name == MANIFEST {
+            manifest = Some(String::from_utf8(data).map_err(|e| invalid(e.to_string()))?);
+        } else {
+            files.insert(safe_path(&name)?, data);
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid("archive has no manifest".to_string()))?;
+    let mut listed = BTreeSet::new();
+    for line in manifest.lines().filter(|line| !line.is_empty()) {
+        let (checksum, name) = line
+            .split_once("  ")
+            .ok_or_else(|| invalid(format!("bad manifest line `{}`", line)))?;
+        let path = safe_path(name)?;
+        let data = files
+            .get(&path)
+            .ok_or_else(|| invalid(format!("`{}` is missing from the archive", name)))?;
+        if sha256(data) != checksum {
+            return Err(invalid(format!("`{}` is corrupted", name)));
+        }
+        listed.insert(path);
+    }
+    if let Some(extra) = files.keys().find(|path| !listed.contains(*path)) {
+        return Err(invalid(format!(
+            "`{}` is not in the manifest",
+            extra.display()
+        )));
+    }
+    Ok(files)
+}
+
+/// Returns the date a journal file belongs to, from a name such as `2024-01-15_entry.md` or
+/// `2024-01-15.log`.
+fn file_date(path: &Path) -> Option<Date> {
+    let name = path.file_name()?.to_str()?;
+    journal::parse_date(name.get(..10)?)
+}
+
+/// Returns true when `path` exists under `root` with other contents than `data`.
+fn differs(root: &Path, path: &Path, data: &[u8]) -> bool {
+    match fs::read(root.join(path)) {
+        Ok(existing) => existing != data,
+        Err(_) => false,
+    }
+}
+
+/// Returns a folder of `.restored`, such as `.restored/2`, that does not exist under `root`.
+fn restored_folder(root: &Path) -> PathBuf {
+    let mut n = 1;
+    loop {
+        let folder = Path::new(RESTORED_DIR).join(n.to_string());
+        if !root.join(&folder).exists() {
+            return folder;
+        }
+        n += 1;
+    }
+}
+
+/// Writes the files of an archive under `root`.
+///
+/// Files of a date conflict when any of them exists with other contents; the whole date is then
+/// handled by `merge`, so an entry never ends up with another day's metadata. Other files
+/// conflict on their own. Differing vault files always fail, since entries sealed under two
+/// keys cannot be mixed.
+///
+/// Renamed files keep their paths inside the restored folder, since encrypted files only open
+/// under their own names, and the vault file is copied next to them.
+pub fn restore(
+    files: &BTreeMap<PathBuf, Vec<u8>>,
+    root: &Path,
+    merge: Merge,
+) -> io::Result<Report> {
+    let conflicting_dates: BTreeSet<Date> = files
+        .iter()
+        .filter(|(path, data)| differs(root, path, data))
+        .filter_map(|(path, _)| file_date(path))
+        .collect();
+    let conflicts: Vec<&PathBuf> = files
+        .iter()
+        .filter(|(path, data)| match file_date(path) {
+            Some(date) => conflicting_dates.contains(&date),
+            None => differs(root, path, data),
+        })
+        .map(|(path, _)| path)
+        .collect();
+
+    if let Some(vault) = conflicts
+        .iter()
+        .find(|path| path.file_name().is_some_and(|name| name == VAULT_FILE))
+    {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "`{}` holds a different key; restore into an empty directory instead",
+                vault.display()
+            ),
+        ));
+    }
+    if merge == Merge::Fail && !conflicts.is_empty() {
+        let names: Vec<String> = conflicts
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!("already exists with other contents: {}", names.join(", ")),
+        ));
+    }
+
+    let folder = restored_folder(root);
+    let mut report = Report::default();
+    for (path, data) in files {
+        let target = if conflicts.contains(&path) {
+            match merge {
+                Merge::Skip | Merge::Fail => {
+                    report.skipped.push(path.clone());
+                    continue;
+                }
+                Merge::Rename => {
+                    let renamed = folder.join(path);
+                    report.renamed.push((path.clone(), renamed.clone()));
+                    renamed
+                }
+            }
+        } else if root.join(path).exists() {
+            report.unchanged.push(path.clone());
+            continue;
+        } else {
+            report.written.push(path.clone());
+            path.clone()
+        };
+        let target = root.join(target);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        journal::write_atomic(&target, data)?;
+    }
+    if !report.renamed.is_empty() {
+        let vaults = files
+            .iter()
+            .filter(|(path, _)| path.file_name().is_some_and(|name| name == VAULT_FILE));
+        for (path, data) in vaults {
+            let target = root.join(&folder).join(path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            journal::write_atomic(&target, data)?;
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::{self, Vault};
+
+    #[test]
+    fn test_archive_round_trip_and_merge() {
+        let source = tempfile::tempdir().unwrap();
+        let root = source.path();
+        fs::create_dir_all(root.join("entries/.git")).unwrap();
+        fs::write(root.join("entries/2024-01-02_entry.md"), "old day").unwrap();
+        fs::write(root.join("entries/2024-01-02_meta.toml"), "mood = 3").unwrap();
+        fs::write(root.join("entries/2024-01-03_entry.md"), "new day").unwrap();
+        fs::write(root.join("entries/.git/HEAD"), "ref").unwrap();
+        fs::write(root.join("daydream.toml"), "ascii = true").unwrap();
+
+        let files = journal_files(
+            root,
+            Path::new("entries"),
+            Path::new("daydream.toml"),
+            Path::new("themes"),
+        )
+        .unwrap();
+        assert_eq!(files.len(), 4);
+        let archive = root.join("journal.tar.gz");
+        create(&archive, root, &files).unwrap();
+        let contents = read(&archive).unwrap();
+        assert_eq!(contents.len(), 4);
+
+        let target = tempfile::tempdir().unwrap();
+        let target = target.path();
+        fs::create_dir_all(target.join("entries")).unwrap();
+        fs::write(target.join("entries/2024-01-02_entry.md"), "edited").unwrap();
+        fs::write(target.join("daydream.toml"), "ascii = true").unwrap();
+
+        assert!(restore(&contents, target, Merge::Fail).is_err());
+        let report = restore(&contents, target, Merge::Skip).unwrap();
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(report.unchanged, [PathBuf::from("daydream.toml")]);
+        assert!(!target.join("entries/2024-01-02_meta.toml").exists());
+        assert!(target.join("entries/2024-01-03_entry.md").exists());
+
+        let report = restore(&contents, target, Merge::Rename).unwrap();
+        assert_eq!(report.renamed.len(), 2);
+        let restored = fs::read_to_string(target.join(".restored/1/entries/2024-01-02_entry.md"));
+        assert_eq!(restored.unwrap(), "old day");
+        let report = restore(&contents, target, Merge::Rename).unwrap();
+        assert_eq!(
+            report.renamed[0].1,
+            PathBuf::from(".restored/2/entries/2024-01-02_entry.md")
+        );
+
+        let mut bytes = fs::read(&archive).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        fs::write(&archive, bytes).unwrap();
+        assert!(read(&archive).is_err());
+    }
+
+    #[test]
+    fn test_restore_encrypted_alongside() {
+        let source = tempfile::tempdir().unwrap();
+        let root = source.path();
+        let entries = root.join("entries");
+        fs::create_dir_all(&entries).unwrap();
+        let vault = Vault::create(&entries, "correct horse").unwrap();
+        let entry = entries.join("2024-01-02_entry.md");
+        let sealed = vault.seal(b"old day", &vault::sealed_name(&entry)).unwrap();
+        fs::write(&entry, sealed).unwrap();
+        let files = journal_files(
+            root,
+            Path::new("entries"),
+            Path::new("daydream.toml"),
+            Path::new("themes"),
+        )
+        .unwrap();
+        let archive = root.join("journal.tar.gz");
+        create(&archive, root, &files).unwrap();
+
+        // the same journal, edited since
+        let edited = vault.seal(b"edited", &vault::sealed_name(&entry)).unwrap();
+        fs::write(&entry, edited).unwrap();
+        let report = restore(&read(&archive).unwrap(), root, Merge::Rename).unwrap();
+        let restored = root.join(".restored/1/entries/2024-01-02_entry.md");
+        assert_eq!(report.renamed[0].1, restored.strip_prefix(root).unwrap());
+        let data = fs::read(&restored).unwrap();
+        let opened = vault.open(&data, &vault::sealed_name(&restored)).unwrap();
+        assert_eq!(opened, b"old day");
+        let restored_entries = root.join(".restored/1/entries");
+        assert!(Vault::unlock(&restored_entries, "correct horse").is_ok());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::app::AppState;
+use crate::journal;
+use crate::vault::{self, Vault};
+use crossterm::event::{KeyCode, KeyEvent};
+use pulldown_cmark::{Event, Parser, Tag};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tempfile::TempPath;
+use time::Date;
+
+/// Folder of the entries directory holding a folder of attached files for each date.
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Extensions of files linked as images rather than plain links.
+const IMAGE_EXTENSIONS: [&str; 7] = ["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+/// Returns the folder of the files attached to a date.
+pub fn folder(dir: &Path, date: Date) -> PathBuf {
+    dir.join(ATTACHMENTS_DIR).join(date.to_string())
+}
+
+/// Lists the names of the files attached to a date, sorted.
+pub fn list(dir: &Path, date: Date) -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(folder(dir, date)) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.ends_with(".tmp"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
+/// Returns the name an attached file is sealed with in an encrypted journal: its path in the
+/// entries directory, so a file moved to another date's folder does not open either.
+fn sealed_name(date: Date, name: &str) -> String {
+    format!("{}/{}/{}", ATTACHMENTS_DIR, date, name)
+}
+
+/// Returns the name a file under the entries directory `dir` is sealed with, when it is an
+/// attachment. Used by `daydream encrypt` and `decrypt`, which see attachments as paths.
+pub fn sealed_path_name(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir.join(ATTACHMENTS_DIR)).ok()?;
+    let mut parts = relative.iter().map(|part| part.to_string_lossy());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(date), Some(name), None) => Some(sealed_name(journal::parse_date(&date)?, &name)),
+        _ => None,
+    }
+}
+
+/// Lists every attached file of the journal, for `daydream encrypt` and `decrypt`.
+pub fn all_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let folders = match fs::read_dir(dir.join(ATTACHMENTS_DIR)) {
+        Ok(folders) => folders,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e),
+    };
+    for folder in folders {
+        let folder = folder?.path();
+        if !folder.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&folder)? {
+            let path = entry?.path();
+            if path.is_file() && !path.to_string_lossy().ends_with(".tmp") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Copies a file into the attachments of a date and returns the name it was given: its own,
+/// or with `-1`, `-2`... added when another file already has it. Attaching the same file twice
+/// keeps one copy. An encrypted journal seals the copy and numbers it, `1.jpg`, `2.pdf`..., so
+/// the name it had is only kept in the entry linking to it.
+pub fn attach(dir: &Path, date: Date, source: &Path, vault: Option<&Vault>) -> io::Result<String> {
+    let name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "not a file"))?;
+    let data = fs::read(source)?;
+    let folder = folder(dir, date);
+    fs::create_dir_all(&folder)?;
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name.as_str(), String::new()),
+    };
+    for n in 0.. {
+        let candidate = match (vault, n) {
+            (None, 0) => name.clone(),
+            (None, n) => format!("{}-{}{}", stem, n, extension),
+            (Some(_), n) => format!("{}{}", n + 1, extension),
+        };
+        let path = folder.join(&candidate);
+        match fs::read(&path) {
+            Ok(existing) => {
+                let existing = vault::decode(vault, &sealed_name(date, &candidate), existing);
+                if existing.is_ok_and(|existing| existing == data) {
+                    return Ok(candidate);
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let sealed = vault::encode(vault, &sealed_name(date, &candidate), &data)?;
+                journal::write_atomic(&path, &sealed)?;
+                return Ok(candidate);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("names run out before numbers do")
+}
+
+/// Returns the Markdown linking an entry to one of its attachments, labelled `label`, as an image
+/// when it is one. The link is relative to the entries directory, where the entry is.
+pub fn markdown_link(date: Date, name: &str, label: &str) -> String {
+    let target = format!("{}/{}/{}", ATTACHMENTS_DIR, date, name);
+    let target = if target.contains([' ', '(', ')', '<', '>']) {
+        format!("<{}>", target)
+    } else {
+        target
+    };
+    let extension = name.rsplit_once('.').map(|(_, extension)| extension);
+    let image = extension.is_some_and(|extension| {
+        IMAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+    });
+    format!(
+        "{}[{}]({})",
+        if image { "!" } else { "" },
+        label.replace('[', "\\[").replace(']', "\\]"),
+        target
+    )
+}
+
+/// Returns the attachments an entry links to, relative to the entries directory.
+pub fn linked(text: &str) -> Vec<PathBuf> {
+    Parser::new(text)
+        .filter_map(|event| match event {
+            Event::Start(Tag::Link { dest_url, .. })
+            | Event::Start(Tag::Image { dest_url, .. }) => Some(dest_url),
+            _ => None,
+        })
+        .filter(|target| target.starts_with(&format!("{}/", ATTACHMENTS_DIR)))
+        .map(|target| PathBuf::from(target.as_ref()))
+        .collect()
+}
+
+/// Returns the attachments that entries link to but that are not in the entries directory.
+pub fn missing(
+    dir: &Path,
+    dates: &[Date],
+    vault: Option<&Vault>,
+) -> io::Result<Vec<(Date, PathBuf)>> {
+    let mut missing = Vec::new();
+    for date in dates {
+        let text = journal::read_entry(dir, *date, vault)?.unwrap_or_default();
+        for path in linked(&text) {
+            if !dir.join(&path).is_file() {
+                missing.push((*date, path));
+            }
+        }
+    }
+    Ok(missing)
+}
+
+/// Turns a path typed or pasted into the attach prompt into a path: surrounding quotes and
+/// backslashes before spaces, as terminals add when a file is dropped on them, are removed and
+/// a leading `~` is the home directory.
+pub fn input_path(text: &str) -> PathBuf {
+    let text = text.trim();
+    let text = ['\'', '"']
+        .iter()
+        .find_map(|quote| text.strip_prefix(*quote)?.strip_suffix(*quote))
+        .map_or_else(|| text.replace("\\ ", " "), String::from);
+    match (text.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(text),
+    }
+}
+
+/// Opens a file with the program the system uses for it, without waiting for it.
+pub fn open(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+    let mut child = command
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Opens the file attached to a date as `name`. An encrypted journal's file is decrypted into a
+/// temporary file first, only readable by the user, which is returned so that it is removed
+/// once dropped.
+pub fn open_attached(
+    dir: &Path,
+    date: Date,
+    name: &str,
+    vault: Option<&Vault>,
+) -> io::Result<Option<TempPath>> {
+    let path = folder(dir, date).join(name);
+    let Some(vault) = vault else {
+        open(&path)?;
+        return Ok(None);
+    };
+    let plain = vault::decode(Some(vault), &sealed_name(date, name), fs::read(&path)?)?;
+    let mut file = tempfile::Builder::new()
+        .prefix("daydream-")
+        .suffix(&format!("-{}", name))
+        .tempfile()?;
+    file.write_all(&plain)?;
+    let path = file.into_temp_path();
+    open(&path)?;
+    Ok(Some(path))
+}
+
+/// Position in the attachments panel while it takes the keys.
+#[derive(Default, Clone, Debug)]
+pub struct Selection {
+    pub selected: usize,
+}
+
+/// What a key press did to the attachments panel.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SelectionEvent {
+    /// Still choosing.
+    Pending,
+    /// Enter was pressed on the attachment at this position.
+    Open(usize),
+    /// Esc or `q` was pressed.
+    Close,
+}
+
+impl Selection {
+    /// Applies a key press to a panel of `count` attachments.
+    pub fn input(&mut self, key: &KeyEvent, count: usize) -> SelectionEvent {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return SelectionEvent::Close,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(count.saturating_sub(1)),
+            KeyCode::Enter if self.selected < count => return SelectionEvent::Open(self.selected),
+            _ => {}
+        }
+        SelectionEvent::Pending
+    }
+}
+
+/// Draws the files attached to the selected date, highlighting the chosen one while the panel
+/// takes the keys.
+pub fn draw_attachments(app: &mut AppState, frame: &mut Frame, area: Rect, focus_key: &str) {
+    let names = app.attachment_names(app.selected_date);
+    let selected = app.attachment_selection.as_ref().map(|s| s.selected);
+    let mut lines: Vec<Line> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let mut line = Line::from(name.clone());
+            if selected == Some(i) {
+                line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+            line
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        match selected {
+            Some(_) => format!("`{}` opens it, `Esc` goes back", app.glyphs.enter),
+            None => format!("press `{}` to open one", focus_key),
+        },
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .title("attachments")
+                .border_style(app.theme.panel_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.panel_border),
+        ),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::parse_date;
+
+    #[test]
+    fn test_attach_and_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let date = parse_date("2024-03-13").unwrap();
+        let photo = dir.path().join("kingfisher.JPG");
+        fs::write(&photo, b"first").unwrap();
+
+        assert_eq!(
+            attach(dir.path(), date, &photo, None).unwrap(),
+            "kingfisher.JPG"
+        );
+        assert_eq!(
+            attach(dir.path(), date, &photo, None).unwrap(),
+            "kingfisher.JPG"
+        );
+        fs::write(&photo, b"second").unwrap();
+        assert_eq!(
+            attach(dir.path(), date, &photo, None).unwrap(),
+            "kingfisher-1.JPG"
+        );
+        assert_eq!(
+            list(dir.path(), date),
+            ["kingfisher-1.JPG", "kingfisher.JPG"]
+        );
+
+        let image = markdown_link(date, "kingfisher.JPG", "kingfisher.JPG");
+        assert_eq!(
+            image,
+            "![kingfisher.JPG](attachments/2024-03-13/kingfisher.JPG)"
+        );
+        let spaced = markdown_link(date, "river notes.pdf", "river notes.pdf");
+        assert_eq!(
+            spaced,
+            "[river notes.pdf](<attachments/2024-03-13/river notes.pdf>)"
+        );
+
+        let text = format!("{}\n{}\n[site](https://example.com)", image, spaced);
+        journal::write_entry(dir.path(), date, &text, None).unwrap();
+        let found = missing(dir.path(), &[date], None).unwrap();
+        assert_eq!(
+            found,
+            [(
+                date,
+                PathBuf::from("attachments/2024-03-13/river notes.pdf")
+            )]
+        );
+
+        assert_eq!(input_path("'a b.png'"), PathBuf::from("a b.png"));
+        assert_eq!(input_path(" a\\ b.png "), PathBuf::from("a b.png"));
+
+        // an encrypted journal seals attachments under numbers
+        let sealed = tempfile::tempdir().unwrap();
+        let vault = Vault::create(sealed.path(), "correct horse").unwrap();
+        let name = attach(sealed.path(), date, &photo, Some(&vault)).unwrap();
+        assert_eq!(name, "1.JPG");
+        assert_eq!(
+            attach(sealed.path(), date, &photo, Some(&vault)).unwrap(),
+            "1.JPG"
+        );
+        let path = folder(sealed.path(), date).join(&name);
+        let data = fs::read(&path).unwrap();
+        assert!(vault::is_sealed(&data));
+        assert_eq!(
+            sealed_path_name(sealed.path(), &path).unwrap(),
+            "attachments/2024-03-13/1.JPG"
+        );
+        assert_eq!(
+            vault.open(&data, "attachments/2024-03-13/1.JPG").unwrap(),
+            b"second"
+        );
+        // a sealed attachment moved to another day does not open
+        let other = parse_date("2024-03-14").unwrap();
+        fs::create_dir_all(folder(sealed.path(), other)).unwrap();
+        fs::copy(&path, folder(sealed.path(), other).join(&name)).unwrap();
+        assert!(open_attached(sealed.path(), other, &name, Some(&vault)).is_err());
+        fs::remove_dir_all(folder(sealed.path(), other)).unwrap();
+        assert_eq!(
+            markdown_link(date, &name, "kingfisher.JPG"),
+            "![kingfisher.JPG](attachments/2024-03-13/1.JPG)"
+        );
+        assert_eq!(all_files(sealed.path()).unwrap(), [path]);
+    }
+}
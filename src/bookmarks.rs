@@ -0,0 +1,190 @@
+use crate::glyphs::Glyphs;
+use crate::journal;
+use crate::prompt::centered;
+use crate::theme::Theme;
+use crate::vault::Vault;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use time::Date;
+
+/// Name of the file, in the entries directory, that holds the bookmarks.
+pub const BOOKMARKS_FILE: &str = "bookmarks.toml";
+
+/// Named days, in date order.
+pub type Bookmarks = BTreeMap<Date, String>;
+
+/// Returns the path of the bookmarks file.
+pub fn path(dir: &Path) -> PathBuf {
+    dir.join(BOOKMARKS_FILE)
+}
+
+/// Reads the bookmarks, stored as e.g. `2024-01-15 = "moved in"`. A journal without the file has
+/// none.
+pub fn read(dir: &Path, vault: Option<&Vault>) -> io::Result<Bookmarks> {
+    let Some(text) = journal::read_text(&path(dir), vault)? else {
+        return Ok(Bookmarks::new());
+    };
+    let table: BTreeMap<String, String> =
+        toml::from_str(&text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    table
+        .into_iter()
+        .map(|(key, name)| match journal::parse_date(&key) {
+            Some(date) => Ok((date, name)),
+            None => Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("`{}` in {} is not a date", key, BOOKMARKS_FILE),
+            )),
+        })
+        .collect()
+}
+
+/// Writes the bookmarks, removing the file when there are none.
+pub fn write(dir: &Path, bookmarks: &Bookmarks, vault: Option<&Vault>) -> io::Result<()> {
+    if bookmarks.is_empty() {
+        return match fs::remove_file(path(dir)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    let table: BTreeMap<String, &String> = bookmarks
+        .iter()
+        .map(|(date, name)| (date.to_string(), name))
+        .collect();
+    let text = toml::to_string(&table).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    journal::write_text(&path(dir), &text, vault)
+}
+
+/// State of the bookmark list shown over the calendar.
+#[derive(Default, Clone, Debug)]
+pub struct Picker {
+    /// Position of the highlighted bookmark.
+    pub selected: usize,
+}
+
+/// What a key press did to the bookmark list.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PickerEvent {
+    /// Still choosing.
+    Pending,
+    /// Enter was pressed on a bookmark.
+    Jump(Date),
+    /// `d` or Delete was pressed on a bookmark.
+    Remove(Date),
+    /// Esc or `q` was pressed.
+    Close,
+}
+
+impl Picker {
+    /// Opens the list on the first bookmark on or after `date`, or on the last one.
+    pub fn new(bookmarks: &Bookmarks, date: Date) -> Self {
+        let selected = bookmarks.range(date..).count();
+        Picker {
+            selected: bookmarks.len().saturating_sub(selected.max(1)),
+        }
+    }
+
+    /// Applies a key press to the list.
+    pub fn input(&mut self, key: &KeyEvent, bookmarks: &Bookmarks) -> PickerEvent {
+        let date = bookmarks.keys().nth(self.selected).copied();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => return PickerEvent::Close,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(bookmarks.len().saturating_sub(1))
+            }
+            KeyCode::Enter => {
+                if let Some(date) = date {
+                    return PickerEvent::Jump(date);
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if let Some(date) = date {
+                    return PickerEvent::Remove(date);
+                }
+            }
+            _ => {}
+        }
+        PickerEvent::Pending
+    }
+}
+
+/// Draws the bookmark list in a box centred in `area`.
+pub fn draw_picker(
+    picker: &Picker,
+    bookmarks: &Bookmarks,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let mut lines: Vec<Line> = bookmarks
+        .iter()
+        .map(|(date, name)| Line::from(format!("{}  {}", date, name)))
+        .collect();
+    if let Some(line) = lines.get_mut(picker.selected) {
+        line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from("no bookmarks yet; press `b` on a day"));
+    }
+
+    let area = centered(area, 50, lines.len() as u16 + 4);
+    // keep the highlighted bookmark in view when the list is taller than the box
+    let visible = area.height.saturating_sub(4) as usize;
+    let scroll = (picker.selected + 1).saturating_sub(visible);
+    let mut lines: Vec<Line> = lines.into_iter().skip(scroll).take(visible).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        format!("{} jump, d remove", glyphs.enter),
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title("bookmarks")
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        assert!(read(dir, None).unwrap().is_empty());
+
+        let date = |text| journal::parse_date(text).unwrap();
+        let mut bookmarks = Bookmarks::new();
+        bookmarks.insert(date("2024-01-15"), "moved in".to_string());
+        bookmarks.insert(date("2023-06-01"), "first \"release\"".to_string());
+        write(dir, &bookmarks, None).unwrap();
+        assert_eq!(read(dir, None).unwrap(), bookmarks);
+
+        // the list opens on the next bookmark, or the last one after them all
+        assert_eq!(Picker::new(&bookmarks, date("2023-07-01")).selected, 1);
+        assert_eq!(Picker::new(&bookmarks, date("2020-01-01")).selected, 0);
+        assert_eq!(Picker::new(&bookmarks, date("2025-01-01")).selected, 1);
+
+        write(dir, &Bookmarks::new(), None).unwrap();
+        assert!(!path(dir).exists());
+
+        fs::write(path(dir), "someday = \"?\"").unwrap();
+        assert!(read(dir, None).is_err());
+    }
+}
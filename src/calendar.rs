@@ -1,11 +1,16 @@
 use crate::app::AppState;
+use crate::bookmarks::Bookmarks;
+use crate::onthisday::{draw_side_panels, PANEL_WIDTH};
+use crate::prompt::centered;
+use crate::theme::Theme;
+use crate::tracker::apply_overlay;
 use core::fmt;
 use ratatui::{
     prelude::*,
-    widgets::{calendar::*, Block, BorderType, Borders},
+    widgets::{calendar::*, Block, Borders, Paragraph, Wrap},
 };
 use std::{collections::HashMap, rc::Rc};
-use time::{Date, Month, OffsetDateTime};
+use time::{Date, Duration, Month, OffsetDateTime, Weekday};
 
 /// Holds information about calendar events and holidays.
 #[derive(Clone, PartialEq)]
@@ -14,134 +19,482 @@ pub struct CalendarInfo {
     pub events: CalendarEventStore,
     /// Maps dates to their holiday names.
     pub holidays: HashMap<Date, String>,
+    /// Maps bookmarked dates to their names.
+    pub bookmarks: Bookmarks,
 }
 
 impl fmt::Display for CalendarInfo {
     /// Formats the calendar info as a string.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Events: {:?} Holidays: {:?}", self.events, self.holidays)
+        write!(
+            f,
+            "Events: {:?} Holidays: {:?} Bookmarks: {:?}",
+            self.events, self.holidays, self.bookmarks
+        )
     }
 }
 
-/// Draws the calendar in the given frame using the application state.
-pub fn draw_calendar(app: &mut AppState, frame: &mut Frame) {
-    let frame_size = frame.size();
+/// How the calendar lays out its days.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum CalendarView {
+    /// Twelve months from January of the selected year.
+    #[default]
+    Year,
+    /// The three months of the selected quarter.
+    Quarter,
+    /// The selected month, with a preview of the entry in each day.
+    Month,
+    /// The selected week, days side by side with longer previews.
+    Week,
+}
+
+impl CalendarView {
+    /// Returns the view that follows this one, wrapping back to `Year`.
+    pub fn next(self) -> Self {
+        match self {
+            CalendarView::Year => CalendarView::Quarter,
+            CalendarView::Quarter => CalendarView::Month,
+            CalendarView::Month => CalendarView::Week,
+            CalendarView::Week => CalendarView::Year,
+        }
+    }
+}
+
+impl fmt::Display for CalendarView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CalendarView::Year => write!(f, "year"),
+            CalendarView::Quarter => write!(f, "quarter"),
+            CalendarView::Month => write!(f, "month"),
+            CalendarView::Week => write!(f, "week"),
+        }
+    }
+}
+
+/// Height of the title bar above the calendar: two lines of hints and the borders.
+const TITLE_HEIGHT: u16 = 4;
+
+/// Width of a month calendar: seven three-column days and the borders.
+const MONTH_WIDTH: u16 = 7 * 3 + 2;
+
+/// Extra width of a month calendar that shows week numbers.
+const WEEK_NUMBER_WIDTH: u16 = 2;
 
-    let constraints = vec![Constraint::Percentage(15), Constraint::Percentage(85)];
+/// Height of a month calendar: the month and weekday headers, up to six weeks and the borders.
+const MONTH_HEIGHT: u16 = 2 + 6 + 2;
 
+/// Smallest area the month view can show six weeks of day cells in, each a label and one line.
+const MONTH_VIEW_SIZE: (u16, u16) = (7 * 5 + 2, 3 + 6 * 2);
+
+/// Smallest area the week view can show seven day columns in, inside its border.
+const WEEK_VIEW_SIZE: (u16, u16) = (7 * 6 + 2, 6);
+
+/// Arrangements of months as rows and columns, most months first.
+const MONTH_GRIDS: [(u16, u16); 10] = [
+    (3, 4),
+    (4, 3),
+    (2, 6),
+    (6, 2),
+    (2, 3),
+    (3, 2),
+    (1, 6),
+    (6, 1),
+    (1, 3),
+    (3, 1),
+];
+
+/// Splits the screen into the title bar and the calendar below it.
+pub fn split_screen(area: Rect) -> (Rect, Rect) {
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(frame_size);
+        .constraints([Constraint::Length(TITLE_HEIGHT), Constraint::Min(0)])
+        .split(area);
+    (layout[0], layout[1])
+}
 
-    let calendar_area = layout[1];
+/// Picks the largest arrangement of at most `most` months, each `width` wide, that fits in
+/// `area`, as rows and columns. Returns `None` when not even one month fits.
+fn month_grid(area: Rect, most: u16, width: u16) -> Option<(u16, u16)> {
+    MONTH_GRIDS
+        .iter()
+        .chain([(1, 1)].iter())
+        .filter(|(rows, cols)| rows * cols <= most)
+        .find(|(rows, cols)| cols * width <= area.width && rows * MONTH_HEIGHT <= area.height)
+        .copied()
+}
 
-    let calarea = Rect {
-        x: calendar_area.x,
-        y: calendar_area.y,
-        width: calendar_area.width,
-        height: calendar_area.height,
-    };
+/// Returns the width of a month calendar, wider when it shows week numbers.
+fn month_width(app: &AppState) -> u16 {
+    if app.config.week_numbers {
+        MONTH_WIDTH + WEEK_NUMBER_WIDTH
+    } else {
+        MONTH_WIDTH
+    }
+}
 
-    let mut start = app.selected_date;
+/// Draws the calendar in the given frame using the application state.
+pub fn draw_calendar(app: &mut AppState, frame: &mut Frame) {
+    let (_, mut calendar_area) = split_screen(frame.size());
+    if app.side_panels_shown() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+            .split(calendar_area);
+        calendar_area = columns[0];
+        draw_side_panels(app, frame, columns[1], "1-9", "f");
+    }
 
     let mut holidays: HashMap<Date, String> = HashMap::new();
-    let holiday_info = make_dates(start.year(), app, &mut holidays);
+    let holiday_info = make_dates(app.selected_date.year(), app, &mut holidays);
     app.holiday_info = Some(holiday_info.clone());
 
-    for (_i, chunk) in split_rows(&calarea)
+    let fits = |(width, height): (u16, u16)| {
+        calendar_area.width >= width && calendar_area.height >= height
+    };
+    match app.calendar_view {
+        CalendarView::Year | CalendarView::Quarter => {
+            let most = if app.calendar_view == CalendarView::Year {
+                12
+            } else {
+                3
+            };
+            let width = month_width(app);
+            match month_grid(calendar_area, most, width) {
+                Some((rows, cols)) => {
+                    draw_months(app, frame, calendar_area, rows, cols, &holiday_info)
+                }
+                None => draw_too_small(app, frame, calendar_area, (width, MONTH_HEIGHT)),
+            }
+        }
+        CalendarView::Month if fits(MONTH_VIEW_SIZE) => {
+            draw_month_view(app, frame, calendar_area, &holiday_info)
+        }
+        CalendarView::Month => draw_too_small(app, frame, calendar_area, MONTH_VIEW_SIZE),
+        CalendarView::Week if fits(WEEK_VIEW_SIZE) => {
+            draw_week_view(app, frame, calendar_area, &holiday_info)
+        }
+        CalendarView::Week => draw_too_small(app, frame, calendar_area, WEEK_VIEW_SIZE),
+    }
+}
+
+/// Says the terminal is too small for the view instead of drawing clipped widgets.
+fn draw_too_small(app: &AppState, frame: &mut Frame, area: Rect, (width, height): (u16, u16)) {
+    let notice = format!(
+        "terminal too small {}\nthe {} view needs {}x{} below the title, there is {}x{}",
+        app.glyphs.shrug, app.calendar_view, width, height, area.width, area.height
+    );
+    frame.render_widget(
+        Paragraph::new(notice)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+/// Returns the first day of a month, if it is within the dates the calendar can show.
+fn first_of_month(year: i32, month: Month) -> Option<Date> {
+    Date::from_calendar_date(year, month, 1).ok()
+}
+
+/// Returns the first day of the month after the one `date` is in, unless that is past the last
+/// date the calendar can show.
+fn next_month(date: Date) -> Option<Date> {
+    if date.month() == Month::December {
+        first_of_month(date.year() + 1, Month::January)
+    } else {
+        first_of_month(date.year(), date.month().next())
+    }
+}
+
+/// Returns the three-letter name of a weekday, e.g. `Mon`.
+fn short_weekday(date: Date) -> String {
+    date.weekday().to_string().chars().take(3).collect()
+}
+
+/// Returns the day that starts the week `date` is in, for weeks starting on `first`, or the
+/// first date the calendar can show when that week starts before it.
+pub fn week_start(date: Date, first: Weekday) -> Date {
+    let offset =
+        (date.weekday().number_days_from_monday() + 7 - first.number_days_from_monday()) % 7;
+    date.checked_sub(Duration::days(offset as i64))
+        .unwrap_or(Date::MIN)
+}
+
+/// Returns the ISO number of the week starting on `start`: that of its fourth day, which is the
+/// ISO week most of its days belong to whatever day weeks start on.
+fn week_number(start: Date) -> u8 {
+    start
+        .checked_add(Duration::days(3))
+        .unwrap_or(start)
+        .iso_week()
+}
+
+/// Draws `rows` by `cols` month calendars, each centred in its share of the area.
+///
+/// The months shown are the block of the year that holds the selected month, so that twelve
+/// months start in January, six in January or July and three at the start of a quarter.
+fn draw_months(
+    app: &AppState,
+    frame: &mut Frame,
+    area: Rect,
+    rows: u16,
+    cols: u16,
+    holiday_info: &CalendarInfo,
+) {
+    let count = rows * cols;
+    let first = (app.selected_date.month() as u16 - 1) / count * count + 1;
+    let Some(mut start) = Month::try_from(first as u8)
+        .ok()
+        .and_then(|month| first_of_month(app.selected_date.year(), month))
+    else {
+        return;
+    };
+    for chunk in split_rows(&area, rows as u32)
         .iter()
-        .flat_map(|row| split_cols(row).to_vec())
-        .enumerate()
+        .flat_map(|row| split_cols(row, cols as u32).to_vec())
     {
-        let cal = cals::get_cal(start.month(), start.year(), &holiday_info.events);
-
-        // Define minimum and maximum padding values
-        let min_horizontal_padding: u16 = 2;
-        let max_horizontal_padding: u16 = 10;
-        let min_vertical_padding: u16 = 0;
-        let max_vertical_padding: u16 = 5;
-
-        // Calculate percentage-based padding
-        let horizontal_padding_percentage = 35;
-        let vertical_padding_percentage = 13;
-
-        // Calculate dynamic padding based on the frame size
-        let mut horizontal_padding = (frame.size().width * horizontal_padding_percentage / 100) / 2; // Divide by 2 for padding on both sides
-        let mut vertical_padding = (frame.size().height * vertical_padding_percentage / 100) / 2; // Divide by 2 for padding on both sides
-
-        // Apply minimum and maximum constraints
-        horizontal_padding = horizontal_padding
-            .max(min_horizontal_padding)
-            .min(max_horizontal_padding);
-        vertical_padding = vertical_padding
-            .max(min_vertical_padding)
-            .min(max_vertical_padding);
-
-        // Calculate the padded chunk
-        let padded_chunk = Rect {
-            x: chunk.x + horizontal_padding,
-            y: chunk.y + vertical_padding,
-            width: chunk.width.saturating_sub(horizontal_padding * 2),
-            height: chunk.height.saturating_sub(vertical_padding * 2),
-        };
-
+        let cal = cals::get_cal(
+            start.month(),
+            start.year(),
+            &holiday_info.events,
+            &app.theme,
+            &app.config,
+        );
         frame.render_widget(
             cal.block(
                 Block::default()
                     .borders(Borders::all())
-                    .border_type(BorderType::Rounded)
+                    .border_set(app.glyphs.panel_border)
                     .title_alignment(Alignment::Center),
             ),
-            padded_chunk,
+            centered(chunk, month_width(app), MONTH_HEIGHT),
         );
-        if start.month().next() == Month::January {
-            start = start
-                .replace_day(1)
-                .unwrap()
-                .replace_year(start.year() + 1)
-                .unwrap()
-                .replace_month(start.month().next())
-                .unwrap();
-        } else {
-            start = start
-                .replace_day(1)
-                .unwrap()
-                .replace_month(start.month().next())
-                .unwrap();
+        // the calendar ends with year 9999
+        let Some(next) = next_month(start) else {
+            break;
+        };
+        start = next;
+    }
+}
+
+/// Returns the style of a day, as the month calendars would show it.
+fn day_style(app: &AppState, date: Date, in_month: bool, holiday_info: &CalendarInfo) -> Style {
+    if in_month {
+        app.theme.day.patch(holiday_info.events.get_style(date))
+    } else {
+        app.theme.surrounding
+    }
+}
+
+/// Draws one day as a cell headed by its label, with the preview of its entry below.
+fn draw_day(
+    app: &mut AppState,
+    frame: &mut Frame,
+    area: Rect,
+    date: Date,
+    label: String,
+    in_month: bool,
+    holiday_info: &CalendarInfo,
+) {
+    let style = day_style(app, date, in_month, holiday_info);
+    let label = match holiday_info.bookmarks.get(&date) {
+        Some(name) => format!("{} {}", label, name),
+        None => label,
+    };
+    let text_style = if in_month {
+        app.theme.day
+    } else {
+        app.theme.surrounding
+    };
+    let preview = app.entry_preview(date).unwrap_or_default().to_string();
+    // cells too short for a box keep only the line their label sits on
+    let borders = if area.height >= 4 {
+        Borders::all()
+    } else {
+        Borders::TOP
+    };
+    let block = Block::default()
+        .borders(borders)
+        .border_set(app.glyphs.panel_border)
+        .border_style(app.theme.panel_border)
+        .title(Span::styled(label, style));
+    frame.render_widget(
+        Paragraph::new(preview)
+            .style(text_style)
+            .wrap(Wrap { trim: true })
+            .block(block),
+        area,
+    );
+}
+
+/// Draws the selected month as a grid of six weeks, each day showing a preview of its entry.
+fn draw_month_view(app: &mut AppState, frame: &mut Frame, area: Rect, holiday_info: &CalendarInfo) {
+    let first = app.selected_date.replace_day(1).unwrap();
+    let block = Block::default()
+        .borders(Borders::all())
+        .border_set(app.glyphs.panel_border)
+        .border_style(app.theme.panel_border)
+        .title(Span::styled(
+            format!("{} {}", first.month(), first.year()),
+            app.theme.month_header,
+        ))
+        .title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    let first_weekday = app.config.first_weekday.into();
+    // a narrow column on the left holds the week numbers, when they are shown
+    let number_width = if app.config.week_numbers { 3 } else { 0 };
+    let split_number = |area: &Rect| {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(number_width), Constraint::Min(0)])
+            .split(*area)
+    };
+
+    let header = split_number(&layout[0]);
+    frame.render_widget(
+        Paragraph::new("Wk").style(app.theme.weekday_header),
+        header[0],
+    );
+    let mut day = week_start(first, first_weekday);
+    for cell in split_cols(&header[1], 7).iter() {
+        frame.render_widget(
+            Paragraph::new(short_weekday(day))
+                .style(app.theme.weekday_header)
+                .alignment(Alignment::Center),
+            *cell,
+        );
+        let Some(next) = day.next_day() else {
+            break;
+        };
+        day = next;
+    }
+
+    // rows of equal height, so that every day gets the same kind of cell
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [Constraint::Length(layout[1].height / 6); 6]
+                .iter()
+                .chain([Constraint::Min(0)].iter()),
+        )
+        .split(layout[1]);
+    let mut day = week_start(first, first_weekday);
+    for row in rows.iter().take(6) {
+        let row = split_number(row);
+        frame.render_widget(
+            Paragraph::new(format!("{:>2}", week_number(day))).style(app.theme.weekday_header),
+            row[0],
+        );
+        for cell in split_cols(&row[1], 7).iter() {
+            let label = format!("{:>2}", day.day());
+            let in_month = day.month() == first.month();
+            draw_day(app, frame, *cell, day, label, in_month, holiday_info);
+            let Some(next) = day.next_day() else {
+                return;
+            };
+            day = next;
         }
     }
 }
 
-/// Splits the given area into equal horizontal rows.
-fn split_rows(area: &Rect) -> Rc<[Rect]> {
+/// Draws the selected week as seven columns, each day showing a longer preview of its entry.
+fn draw_week_view(app: &mut AppState, frame: &mut Frame, area: Rect, holiday_info: &CalendarInfo) {
+    let mut day = week_start(app.selected_date, app.config.first_weekday.into());
+    let mut title = format!("{} {}", day.month(), day.year());
+    if app.config.week_numbers {
+        title.push_str(&format!(", week {}", week_number(day)));
+    }
+    let block = Block::default()
+        .borders(Borders::all())
+        .border_set(app.glyphs.panel_border)
+        .border_style(app.theme.panel_border)
+        .title(Span::styled(title, app.theme.month_header))
+        .title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    for cell in split_cols(&inner, 7).iter() {
+        let label = format!("{} {}", short_weekday(day), day.day());
+        draw_day(app, frame, *cell, day, label, true, holiday_info);
+        let Some(next) = day.next_day() else {
+            break;
+        };
+        day = next;
+    }
+}
+
+/// Splits the given area into `count` equal horizontal rows.
+fn split_rows(area: &Rect, count: u32) -> Rc<[Rect]> {
     let list_layout = Layout::default()
         .direction(Direction::Vertical)
         .margin(0)
-        .constraints([
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-        ]);
+        .constraints((0..count).map(|_| Constraint::Ratio(1, count)));
 
     list_layout.split(*area)
 }
 
-/// Splits the given area into equal vertical columns.
-fn split_cols(area: &Rect) -> Rc<[Rect]> {
+/// Splits the given area into `count` equal vertical columns.
+fn split_cols(area: &Rect, count: u32) -> Rc<[Rect]> {
     let list_layout = Layout::default()
         .direction(Direction::Horizontal)
         .margin(0)
-        .constraints([
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-        ]);
+        .constraints((0..count).map(|_| Constraint::Ratio(1, count)));
 
     list_layout.split(*area)
 }
 
+/// A holiday or season change shown on the calendar.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Holiday {
+    pub date: Date,
+    pub name: &'static str,
+    /// Festive touch shown after the name, and its ASCII substitute.
+    pub unicode: &'static str,
+    pub ascii: &'static str,
+    /// Whether this is an equinox or solstice rather than a holiday.
+    pub season: bool,
+}
+
+/// Month, day, name, festive touches and whether it is a season change, for every holiday.
+#[rustfmt::skip]
+const HOLIDAYS: [(Month, u8, &str, &str, &str, bool); 14] = [
+    (Month::January, 1, "New Year's Day", "\u{1F389}", "\\o/", false), // Party Popper
+    (Month::February, 2, "Groundhog Day", "🦫", "(:>", false), // Beaver
+    (Month::February, 14, "Valentine's Day", "\u{1F496}", "<3", false), // Heart with Arrow
+    (Month::April, 1, "April Fool's Day", "🃏", ":P", false), // Joker
+    (Month::April, 22, "Earth Day", "🌏", "(@)", false), // Globe
+    (Month::June, 19, "Juneteenth", "🎆", "*", false), // Fireworks
+    (Month::July, 4, "Independence Day", "🕌", "*", false), // United States Flag
+    (Month::December, 24, "Christmas Eve", "\u{1F384}", "<*>", false), // Christmas Tree
+    (Month::December, 25, "Christmas Day", "\u{1F384}", "<*>", false), // Christmas Tree
+    (Month::December, 31, "New Year's Eve", "\u{1F55B}", "\\o/", false), // Celebration
+    (Month::March, 22, "Spring Equinox", "\u{1F331}", ",.,", true), // Seedling
+    (Month::June, 21, "Summer Solstice", "\u{2600}", "(O)", true), // Sun
+    (Month::September, 22, "Fall Equinox", "\u{1F342}", "~", true), // Maple Leaf
+    (Month::December, 21, "Winter Solstice", "\u{26C4}", "*", true), // Snowman
+];
+
+/// Returns the holidays and season changes of a year, in the order they are listed.
+pub fn holidays_of_year(year: i32) -> Vec<Holiday> {
+    HOLIDAYS
+        .iter()
+        .filter_map(|&(month, day, name, unicode, ascii, season)| {
+            Some(Holiday {
+                date: Date::from_calendar_date(year, month, day).ok()?,
+                name,
+                unicode,
+                ascii,
+                season,
+            })
+        })
+        .collect()
+}
+
 /// Populates calendar info with holidays and styles for a given year.
 fn make_dates(
     current_year: i32,
@@ -149,20 +502,10 @@ fn make_dates(
     holidays: &mut HashMap<Date, String>,
 ) -> CalendarInfo {
     let mut list = match OffsetDateTime::now_local() {
-        Ok(_datetime) => CalendarEventStore::today(
-            Style::default()
-                .add_modifier(Modifier::SLOW_BLINK)
-                .fg(Color::Rgb(55, 55, 255))
-                .bg(Color::Rgb(255, 255, 160)),
-        ),
+        Ok(_datetime) => CalendarEventStore::today(app.theme.today),
         Err(e) => {
             eprintln!("Failed to get local date time: {}", e);
-            CalendarEventStore::today(
-                Style::default()
-                    .add_modifier(Modifier::SLOW_BLINK)
-                    .fg(Color::Rgb(55, 55, 255))
-                    .bg(Color::Rgb(255, 255, 160)),
-            )
+            CalendarEventStore::today(app.theme.today)
         }
     };
 
@@ -175,131 +518,23 @@ fn make_dates(
         holidays.insert(date, format!("{} {}", name, festive_touch));
     };
 
-    let holiday_style = Style::default()
-        .add_modifier(Modifier::UNDERLINED)
-        .fg(Color::Yellow)
-        .bg(Color::Rgb(70, 100, 255));
-
-    let selected_style = Style::default()
-        .add_modifier(Modifier::CROSSED_OUT)
-        .bg(Color::White);
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::January, 1).unwrap(),
-        "New Year's Day",
-        holiday_style,
-        "\u{1F389}", // Party Popper
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::February, 2).unwrap(),
-        "Groundhog Day",
-        holiday_style,
-        "🦫", // Panda Face
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::February, 14).unwrap(),
-        "Valentine's Day",
-        holiday_style,
-        "\u{1F496}", // Heart with Arrow
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::April, 1).unwrap(),
-        "April Fool's Day",
-        holiday_style,
-        "🃏", // Laughing Face with Sweat
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::April, 22).unwrap(),
-        "Earth Day",
-        holiday_style,
-        "🌏", //
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::June, 19).unwrap(),
-        "Juneteenth",
-        holiday_style,
-        "🎆", //
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::July, 4).unwrap(),
-        "Independence Day",
-        holiday_style,
-        "🕌", // United States Flag
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::December, 24).unwrap(),
-        "Christmas Eve",
-        holiday_style,
-        "\u{1F384}", // Christmas Tree
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::December, 25).unwrap(),
-        "Christmas Day",
-        holiday_style,
-        "\u{1F384}", // Christmas Tree
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::December, 31).unwrap(),
-        "New Year's Eve",
-        holiday_style,
-        "\u{1F55B}", // Celebration
-        holidays,
-    );
-
-    let season_style = Style::default()
-        .fg(Color::Red)
-        .bg(Color::LightYellow)
-        .add_modifier(Modifier::BOLD);
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::March, 22).unwrap(),
-        "Spring Equinox",
-        season_style,
-        "\u{1F331}", // Seedling
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::June, 21).unwrap(),
-        "Summer Solstice",
-        season_style,
-        "\u{2600}", // Sun
-        holidays,
-    );
-
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::September, 22).unwrap(),
-        "Fall Equinox",
-        season_style,
-        "\u{1F342}", // Maple Leaf
-        holidays,
-    );
+    let g = app.glyphs;
+    for holiday in holidays_of_year(current_year) {
+        let style = if holiday.season {
+            app.theme.season
+        } else {
+            app.theme.holiday
+        };
+        add_holiday(
+            holiday.date,
+            holiday.name,
+            style,
+            g.pick(holiday.unicode, holiday.ascii),
+            holidays,
+        );
+    }
 
-    add_holiday(
-        Date::from_calendar_date(current_year, Month::December, 21).unwrap(),
-        "Winter Solstice",
-        season_style,
-        "\u{26C4}", // Snowman
-        holidays,
-    );
+    let selected_style = app.theme.selected;
 
     let reference_holidays: HashMap<Date, String> = holidays.clone();
 
@@ -321,29 +556,202 @@ fn make_dates(
         );
     }
 
+    let selected_date = app.selected_date;
+    let bookmarks = app.journal_index().bookmarks.clone();
+    for date in bookmarks.keys().filter(|date| **date != selected_date) {
+        let base = list.0.get(date).copied().unwrap_or_default();
+        list.add(*date, base.patch(app.theme.bookmark));
+    }
+
+    let overlay = app.overlay;
+    let theme = app.theme.clone();
+    apply_overlay(
+        overlay,
+        &theme,
+        app.journal_index(),
+        &mut list,
+        selected_date,
+    );
+
     CalendarInfo {
         events: list,
         holidays: holidays.clone(),
+        bookmarks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use ratatui::widgets::Widget;
+
+    #[test]
+    fn test_month_grid() {
+        let area = |width, height| Rect::new(0, 0, width, height);
+        assert_eq!(month_grid(area(120, 34), 12, MONTH_WIDTH), Some((3, 4)));
+        assert_eq!(month_grid(area(80, 18), 12, MONTH_WIDTH), Some((1, 3)));
+        assert_eq!(month_grid(area(80, 24), 12, MONTH_WIDTH), Some((2, 3)));
+        assert_eq!(month_grid(area(200, 24), 12, MONTH_WIDTH), Some((2, 6)));
+        assert_eq!(month_grid(area(200, 60), 3, MONTH_WIDTH), Some((1, 3)));
+        assert_eq!(month_grid(area(30, 12), 12, MONTH_WIDTH), Some((1, 1)));
+        assert_eq!(month_grid(area(22, 40), 12, MONTH_WIDTH), None);
+        assert_eq!(month_grid(area(92, 40), 12, MONTH_WIDTH + 2), Some((4, 3)));
+    }
+
+    #[test]
+    fn test_weeks() {
+        let date = |year, month, day| Date::from_calendar_date(year, month, day).unwrap();
+        // a Thursday
+        let new_year = date(2026, Month::January, 1);
+        let monday = date(2025, Month::December, 29);
+        assert_eq!(week_start(new_year, Weekday::Monday), monday);
+        assert_eq!(week_start(new_year, Weekday::Thursday), new_year);
+        assert_eq!(
+            week_start(new_year, Weekday::Sunday),
+            date(2025, Month::December, 28)
+        );
+        assert_eq!(week_number(monday), 1);
+        assert_eq!(week_number(week_start(new_year, Weekday::Sunday)), 1);
+        let october = date(2026, Month::October, 19);
+        assert_eq!(week_number(week_start(october, Weekday::Saturday)), 43);
+    }
+
+    #[test]
+    fn test_calendar_edges() {
+        assert_eq!(next_month(Date::MAX), None);
+        assert_eq!(week_start(Date::MIN, Weekday::Sunday), Date::MIN);
+        let theme = Theme::default();
+        let config = Config {
+            week_numbers: true,
+            ..Config::default()
+        };
+        for date in [Date::MIN, Date::MAX] {
+            let cal = cals::get_cal(
+                date.month(),
+                date.year(),
+                CalendarEventStore::default(),
+                &theme,
+                &config,
+            );
+            let area = Rect::new(0, 0, 30, 10);
+            cal.render(area, &mut Buffer::empty(area));
+        }
     }
 }
 
 mod cals {
     use super::*;
+    use crate::config::Config;
+    use ratatui::widgets::Widget;
 
     /// Fetches the calendar for a given month and year.
-    pub(super) fn get_cal<'a, DS: DateStyler>(m: Month, y: i32, es: DS) -> Monthly<'a, DS> {
-        default(m, y, es)
+    pub(super) fn get_cal<'a, DS: DateStyler>(
+        m: Month,
+        y: i32,
+        es: DS,
+        theme: &'a Theme,
+        config: &Config,
+    ) -> MonthCalendar<'a, DS> {
+        default(m, y, es, theme, config)
     }
 
-    /// Creates a default style calendar for a month and year with custom styles.
-    fn default<'a, DS: DateStyler>(m: Month, y: i32, es: DS) -> Monthly<'a, DS> {
-        let header_style = Style::default().fg(Color::Green);
-        let default_style = Style::default().fg(Color::White).bg(Color::DarkGray);
+    /// Creates a default style calendar for a month and year with the theme's styles.
+    fn default<'a, DS: DateStyler>(
+        m: Month,
+        y: i32,
+        es: DS,
+        theme: &'a Theme,
+        config: &Config,
+    ) -> MonthCalendar<'a, DS> {
+        MonthCalendar {
+            month: Date::from_calendar_date(y, m, 1).unwrap(),
+            events: es,
+            theme,
+            first_weekday: config.first_weekday.into(),
+            week_numbers: config.week_numbers,
+            block: None,
+        }
+    }
 
-        Monthly::new(Date::from_calendar_date(y, m, 1).unwrap(), es)
-            .show_surrounding(Style::default().add_modifier(Modifier::DIM))
-            .show_weekdays_header(header_style)
-            .default_style(default_style)
-            .show_month_header(Style::default())
+    /// A month as a grid of days, like ratatui's `Monthly`, but starting its weeks on any day
+    /// and optionally leading each week with its ISO week number.
+    pub(super) struct MonthCalendar<'a, DS: DateStyler> {
+        month: Date,
+        events: DS,
+        theme: &'a Theme,
+        first_weekday: Weekday,
+        week_numbers: bool,
+        block: Option<Block<'a>>,
+    }
+
+    impl<'a, DS: DateStyler> MonthCalendar<'a, DS> {
+        pub(super) fn block(mut self, block: Block<'a>) -> Self {
+            self.block = Some(block);
+            self
+        }
+
+        /// Lays the month out as lines: the month, the weekdays, then one line per week.
+        fn lines(&self) -> Vec<Line<'static>> {
+            let theme = self.theme;
+            let mut lines = vec![Line::styled(
+                format!("{} {}", self.month.month(), self.month.year()),
+                theme.month_header,
+            )
+            .alignment(Alignment::Center)];
+
+            let start = week_start(self.month, self.first_weekday);
+            let mut header = Vec::new();
+            if self.week_numbers {
+                header.push(Span::styled("Wk", theme.weekday_header));
+            }
+            for day in (0..7).filter_map(|offset| start.checked_add(Duration::days(offset))) {
+                let name: String = short_weekday(day).chars().take(2).collect();
+                header.push(Span::styled(format!(" {}", name), theme.weekday_header));
+            }
+            lines.push(Line::from(header));
+
+            let end = next_month(self.month);
+            let mut next_week = Some(start);
+            while let Some(week) = next_week.filter(|week| end.is_none_or(|end| *week < end)) {
+                let mut spans = Vec::new();
+                if self.week_numbers {
+                    spans.push(Span::styled(
+                        format!("{:>2}", week_number(week)),
+                        theme.weekday_header,
+                    ));
+                }
+                for day in (0..7).filter_map(|offset| week.checked_add(Duration::days(offset))) {
+                    let base = if day.month() == self.month.month() {
+                        theme.day
+                    } else {
+                        theme.surrounding
+                    };
+                    spans.push(Span::styled(" ", theme.day));
+                    spans.push(Span::styled(
+                        format!("{:>2}", day.day()),
+                        base.patch(self.events.get_style(day)),
+                    ));
+                }
+                lines.push(Line::from(spans));
+                next_week = week.checked_add(Duration::WEEK);
+            }
+            lines
+        }
+    }
+
+    impl<'a, DS: DateStyler> Widget for MonthCalendar<'a, DS> {
+        fn render(mut self, area: Rect, buf: &mut Buffer) {
+            let inner = match self.block.take() {
+                Some(block) => {
+                    let inner = block.inner(area);
+                    block.render(area, buf);
+                    inner
+                }
+                None => area,
+            };
+            // a paragraph clips to the area, where `Monthly` would write past a short one
+            Paragraph::new(self.lines()).render(inner, buf);
+        }
     }
 }
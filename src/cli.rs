@@ -0,0 +1,427 @@
+use crate::archive::{self, Merge};
+use crate::attachments;
+use crate::config::{self, CONFIG_PATH};
+use crate::export;
+use crate::history;
+use crate::import::{self, Action, Source};
+use crate::journal::{self, ENTRIES_DIR};
+use crate::revlog;
+use crate::vault::{self, Vault};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use time::Date;
+
+/// Help shown for `daydream help` and after a usage error.
+pub const USAGE: &str = "usage: daydream [command]
+
+Without a command the journal opens in the terminal.
+
+commands:
+  encrypt    encrypt every entry in place, asking for a new passphrase
+  decrypt    decrypt every entry in place and stop asking for a passphrase
+  archive [FILE]
+             bundle entries, metadata, config and themes into a checksummed .tar.gz
+             (default daydream-<today>.tar.gz)
+  restore FILE [--merge skip|rename]
+             verify an archive and restore it here; without --merge nothing is written
+             if a date or file already exists with other contents
+  export FORMAT PATH [--from YYYY-MM-DD] [--to YYYY-MM-DD]
+             export a range of entries (both dates inclusive) as
+               html  a static website with year calendars and tag pages in directory PATH
+               md    one Markdown document with a table of contents
+               json  a JSON array of date, tags, metadata and body
+               txt   one plain-text document
+             md, json and txt write to standard output when PATH is -
+  import jrnl|dayone|obsidian PATH [--yes]
+             list what importing a jrnl text file, a Day One Journal.json or a folder of
+             Obsidian daily notes would do; with --yes, write the entries
+  check      list links to attachments whose files are missing, failing if there are any
+  help       show this message";
+
+/// A subcommand given on the command line.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Command {
+    Encrypt,
+    Decrypt,
+    Archive(Option<PathBuf>),
+    Restore(PathBuf, Merge),
+    Export(Export),
+    Import(Source, PathBuf, bool),
+    Check,
+    Help,
+}
+
+/// Options of `daydream export`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Export {
+    pub format: String,
+    pub output: PathBuf,
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+}
+
+/// Formats `daydream export` accepts.
+const EXPORT_FORMATS: [&str; 4] = ["html", "md", "json", "txt"];
+
+fn parse_export(mut args: impl Iterator<Item = String>) -> Result<Export, String> {
+    let format = args.next().ok_or("export needs a format")?;
+    if !EXPORT_FORMATS.contains(&format.as_str()) {
+        return Err(format!("unknown export format `{}`", format));
+    }
+    let output = args.next().ok_or("export needs an output path")?;
+    if format == "html" && output == "-" {
+        return Err("html export needs an output directory".to_string());
+    }
+    let mut export = Export {
+        format,
+        output: PathBuf::from(output),
+        from: None,
+        to: None,
+    };
+    while let Some(arg) = args.next() {
+        let date = match arg.as_str() {
+            "--from" => &mut export.from,
+            "--to" => &mut export.to,
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        };
+        let value = args.next().ok_or(format!("{} needs a date", arg))?;
+        *date = Some(
+            journal::parse_date(&value).ok_or(format!("`{}` is not a YYYY-MM-DD date", value))?,
+        );
+    }
+    Ok(export)
+}
+
+/// Parses the arguments after the program name, returning `None` when the interface should open.
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Command>, String> {
+    let command = match args.next().as_deref() {
+        None => return Ok(None),
+        Some("encrypt") => Command::Encrypt,
+        Some("decrypt") => Command::Decrypt,
+        Some("archive") => Command::Archive(args.next().map(PathBuf::from)),
+        Some("restore") => {
+            let file = args.next().ok_or("restore needs an archive file")?;
+            let merge = match args.next().as_deref() {
+                None => Merge::Fail,
+                Some("--merge") => match args.next().as_deref() {
+                    Some("skip") => Merge::Skip,
+                    Some("rename") => Merge::Rename,
+                    _ => return Err("--merge takes skip or rename".to_string()),
+                },
+                Some(other) => return Err(format!("unexpected argument `{}`", other)),
+            };
+            if let Some(extra) = args.next() {
+                return Err(format!("unexpected argument `{}`", extra));
+            }
+            Command::Restore(PathBuf::from(file), merge)
+        }
+        Some("export") => return parse_export(args).map(|export| Some(Command::Export(export))),
+        Some("import") => {
+            let source = args.next().ok_or("import needs a source")?;
+            let source =
+                Source::from_name(&source).ok_or(format!("unknown import source `{}`", source))?;
+            let path = args.next().ok_or("import needs a path")?;
+            let yes = match args.next().as_deref() {
+                None => false,
+                Some("--yes") | Some("-y") => true,
+                Some(other) => return Err(format!("unexpected argument `{}`", other)),
+            };
+            Command::Import(source, PathBuf::from(path), yes)
+        }
+        Some("check") => Command::Check,
+        Some("help") | Some("-h") | Some("--help") => Command::Help,
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+    };
+    match args.next() {
+        Some(extra) => Err(format!("unexpected argument `{}`", extra)),
+        None => Ok(Some(command)),
+    }
+}
+
+/// Runs a subcommand against the journal in the working directory.
+pub fn run(command: Command) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = Path::new(ENTRIES_DIR);
+    match command {
+        Command::Encrypt => encrypt(dir)?,
+        Command::Decrypt => decrypt(dir)?,
+        Command::Archive(file) => create_archive(file)?,
+        Command::Restore(file, merge) => restore_archive(&file, merge)?,
+        Command::Export(options) => export(dir, &options)?,
+        Command::Import(source, path, yes) => import(dir, source, &path, yes)?,
+        Command::Check => check(dir)?,
+        Command::Help => println!("{}", USAGE),
+    }
+    Ok(())
+}
+
+/// Lists the entry, metadata and attached files of the journal.
+fn journal_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.path().is_file() && journal::is_journal_file(&entry.file_name().to_string_lossy())
+        {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    files.extend(attachments::all_files(dir)?);
+    Ok(files)
+}
+
+/// Asks for a new passphrase twice.
+fn new_passphrase() -> io::Result<String> {
+    let passphrase = rpassword::prompt_password("new passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "passphrase must not be empty",
+        ));
+    }
+    if rpassword::prompt_password("repeat passphrase: ")? != passphrase {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "passphrases do not match",
+        ));
+    }
+    Ok(passphrase)
+}
+
+/// Encrypts every journal file that is not encrypted yet.
+///
+/// The vault file is written first, so an interrupted run leaves a journal that still opens
+/// and can be finished by decrypting it. A git history is refused, since it would keep every
+/// past version of the entries in plain text.
+pub fn encrypt(dir: &Path) -> io::Result<()> {
+    if vault::exists(dir) {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            "journal is already encrypted",
+        ));
+    }
+    if history::is_repository(dir) {
+        return Err(io::Error::new(
+            ErrorKind::AlreadyExists,
+            format!(
+                "{} holds the unencrypted history of every entry; move it out of the journal \
+                 or delete it, then encrypt again (a new history starts at the next save)",
+                dir.join(".git").display()
+            ),
+        ));
+    }
+    let vault = Vault::create(dir, &new_passphrase()?)?;
+    let files = journal_files(dir)?;
+    for path in &files {
+        let data = fs::read(path)?;
+        if !vault::is_sealed(&data) {
+            journal::write_atomic(path, &vault.seal(&data, &sealed_name(dir, path))?)?;
+        }
+    }
+    for path in revlog::revlog_files(dir)? {
+        revlog::reencode(&path, Some(&vault), Some(&vault))?;
+    }
+    println!("encrypted {} files in {}", files.len(), dir.display());
+    Ok(())
+}
+
+/// Returns the name a journal file is sealed with: its path for attachments, else its file name.
+fn sealed_name(dir: &Path, path: &Path) -> String {
+    attachments::sealed_path_name(dir, path).unwrap_or_else(|| vault::sealed_name(path))
+}
+
+/// Decrypts every journal file and removes the vault file.
+pub fn decrypt(dir: &Path) -> io::Result<()> {
+    if !vault::exists(dir) {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            "journal is not encrypted",
+        ));
+    }
+    let vault = Vault::unlock(dir, &rpassword::prompt_password("passphrase: ")?)?;
+    let files = journal_files(dir)?;
+    for path in &files {
+        let data = fs::read(path)?;
+        if vault::is_sealed(&data) {
+            journal::write_atomic(path, &vault.open(&data, &sealed_name(dir, path))?)?;
+        }
+    }
+    for path in revlog::revlog_files(dir)? {
+        revlog::reencode(&path, Some(&vault), None)?;
+    }
+    fs::remove_file(vault::vault_path(dir))?;
+    println!("decrypted {} files in {}", files.len(), dir.display());
+    if history::is_repository(dir) {
+        eprintln!(
+            "warning: versions committed to {} while the journal was encrypted stay encrypted \
+             and can no longer be read",
+            dir.join(".git").display()
+        );
+    }
+    Ok(())
+}
+
+/// Bundles the journal in the working directory into an archive.
+fn create_archive(file: Option<PathBuf>) -> io::Result<()> {
+    let config = config::load(Path::new(CONFIG_PATH))?;
+    let file = file.unwrap_or_else(|| {
+        let today = time::OffsetDateTime::now_local()
+            .unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+            .date();
+        PathBuf::from(format!("daydream-{}.tar.gz", today))
+    });
+    let root = Path::new(".");
+    let files = archive::journal_files(
+        root,
+        Path::new(ENTRIES_DIR),
+        Path::new(CONFIG_PATH),
+        &config.themes_dir,
+    )?;
+    archive::create(&file, root, &files)?;
+    println!("archived {} files to {}", files.len(), file.display());
+    Ok(())
+}
+
+/// Verifies an archive and restores it into the working directory.
+fn restore_archive(file: &Path, merge: Merge) -> io::Result<()> {
+    let files = archive::read(file)?;
+    println!("verified {} files in {}", files.len(), file.display());
+    let report = archive::restore(&files, Path::new("."), merge)?;
+    for path in &report.skipped {
+        println!("skipped {} (already exists)", path.display());
+    }
+    for (path, renamed) in &report.renamed {
+        println!("restored {} as {}", path.display(), renamed.display());
+    }
+    println!(
+        "restored {} files, {} already up to date, {} skipped, {} renamed",
+        report.written.len(),
+        report.unchanged.len(),
+        report.skipped.len(),
+        report.renamed.len()
+    );
+    Ok(())
+}
+
+/// Asks for the passphrase of an encrypted journal; plain journals need no key.
+fn open_vault(dir: &Path) -> io::Result<Option<Vault>> {
+    if !vault::exists(dir) {
+        return Ok(None);
+    }
+    Vault::unlock(dir, &rpassword::prompt_password("passphrase: ")?).map(Some)
+}
+
+/// Exports the entries of the journal in the requested format.
+fn export(dir: &Path, options: &Export) -> io::Result<()> {
+    let vault = open_vault(dir)?;
+    let entries = export::load_entries(dir, vault.as_ref(), options.from, options.to)?;
+    let document = match options.format.as_str() {
+        "html" => None,
+        "md" => Some(export::markdown_book(&entries)),
+        "json" => Some(export::json_document(&entries)?),
+        "txt" => Some(export::plain_text(&entries)),
+        _ => unreachable!("format checked when parsing"),
+    };
+    match document {
+        None => export::html_site(&entries, &options.output)?,
+        Some(document) if options.output == Path::new("-") => print!("{}", document),
+        Some(document) => journal::write_atomic(&options.output, document.as_bytes())?,
+    }
+    // standard output may be carrying the export itself
+    eprintln!(
+        "exported {} entries to {}",
+        entries.len(),
+        options.output.display()
+    );
+    Ok(())
+}
+
+/// Reads another tool's journal and lists what importing it does, writing only with `yes`.
+fn import(dir: &Path, source: Source, path: &Path, yes: bool) -> io::Result<()> {
+    let imported = import::read(source, path)?;
+    let vault = open_vault(dir)?;
+    let planned = import::plan(&imported, dir, vault.as_ref())?;
+
+    let count = |action| {
+        planned
+            .iter()
+            .filter(|planned| planned.action == action)
+            .count()
+    };
+    for planned in &planned {
+        let action = match planned.action {
+            Action::Create => "new",
+            Action::Append => "append",
+            Action::Unchanged => "already imported",
+        };
+        println!(
+            "{}  {:<16} {} entries",
+            planned.date, action, planned.entries
+        );
+    }
+    println!(
+        "{} entries on {} dates: {} new, {} appended to existing entries, {} already imported",
+        imported.len(),
+        planned.len(),
+        count(Action::Create),
+        count(Action::Append),
+        count(Action::Unchanged)
+    );
+    if !yes {
+        println!("Nothing was written. Run again with --yes to import.");
+        return Ok(());
+    }
+    import::apply(&planned, dir, vault.as_ref())?;
+    println!("imported into {}", dir.display());
+    Ok(())
+}
+
+/// Lists the attachments entries link to that are not in the entries directory.
+fn check(dir: &Path) -> io::Result<()> {
+    let vault = open_vault(dir)?;
+    let dates = journal::entry_dates(dir);
+    let missing = attachments::missing(dir, &dates, vault.as_ref())?;
+    for (date, path) in &missing {
+        println!("{}  missing {}", date, path.display());
+    }
+    println!(
+        "checked {} entries, {} missing attachments",
+        dates.len(),
+        missing.len()
+    );
+    if !missing.is_empty() {
+        return Err(io::Error::new(
+            ErrorKind::NotFound,
+            format!("{} attachments are missing", missing.len()),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_line(line: &str) -> Result<Option<Command>, String> {
+        parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_restore() {
+        assert_eq!(
+            parse_line("restore a.tgz"),
+            Ok(Some(Command::Restore(PathBuf::from("a.tgz"), Merge::Fail)))
+        );
+        assert_eq!(
+            parse_line("restore a.tgz --merge rename"),
+            Ok(Some(Command::Restore(
+                PathBuf::from("a.tgz"),
+                Merge::Rename
+            )))
+        );
+        assert!(parse_line("restore a.tgz --merge skip --yes").is_err());
+        assert!(parse_line("restore a.tgz --merge").is_err());
+        assert!(parse_line("restore a.tgz --yes").is_err());
+        assert!(parse_line("restore").is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::palette::ColorSupport;
+use serde::Deserialize;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use time::Weekday;
+
+/// Location of the configuration file, relative to the working directory.
+pub const CONFIG_PATH: &str = "daydream.toml";
+
+/// User settings read from `daydream.toml`. Every field is optional.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    /// Name of the theme to start with.
+    pub theme: Option<String>,
+    /// Directory searched for user theme files (`*.toml`).
+    pub themes_dir: PathBuf,
+    /// Colour support to assume instead of detecting it from the environment.
+    pub color: Option<ColorSupport>,
+    /// Whether to draw plain ASCII instead of emoji and wide characters.
+    pub ascii: Option<bool>,
+    /// Minutes without input after which the screen locks.
+    pub idle_lock_minutes: Option<u64>,
+    /// Whether every save is committed to a git repository in the entries directory.
+    pub history: bool,
+    /// Whether every save is kept in daydream's own revision log of the entry.
+    pub revisions: bool,
+    /// PIN that unlocks the screen of a journal that is not encrypted.
+    pub lock_pin: Option<String>,
+    /// Day the calendar starts its weeks on.
+    pub first_weekday: FirstWeekday,
+    /// Whether the calendar shows ISO week numbers.
+    pub week_numbers: bool,
+    /// Whether the panel of entries written on the same day in earlier years starts open.
+    pub on_this_day: bool,
+    /// Age in days after which an entry can only be edited once it is deliberately unlocked.
+    pub lock_after_days: Option<u32>,
+}
+
+/// Day of the week the calendar starts each week on.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FirstWeekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    #[default]
+    Sunday,
+}
+
+impl From<FirstWeekday> for Weekday {
+    fn from(day: FirstWeekday) -> Weekday {
+        match day {
+            FirstWeekday::Monday => Weekday::Monday,
+            FirstWeekday::Tuesday => Weekday::Tuesday,
+            FirstWeekday::Wednesday => Weekday::Wednesday,
+            FirstWeekday::Thursday => Weekday::Thursday,
+            FirstWeekday::Friday => Weekday::Friday,
+            FirstWeekday::Saturday => Weekday::Saturday,
+            FirstWeekday::Sunday => Weekday::Sunday,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            theme: None,
+            themes_dir: PathBuf::from("themes"),
+            color: None,
+            ascii: None,
+            idle_lock_minutes: None,
+            history: false,
+            revisions: false,
+            lock_pin: None,
+            first_weekday: FirstWeekday::default(),
+            week_numbers: false,
+            on_this_day: false,
+            lock_after_days: None,
+        }
+    }
+}
+
+/// Loads the configuration file, falling back to defaults when it does not exist.
+pub fn load(path: &Path) -> io::Result<Config> {
+    match fs::read_to_string(path) {
+        Ok(text) => toml::from_str(&text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(e),
+    }
+}
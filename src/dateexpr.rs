@@ -0,0 +1,204 @@
+use crate::journal;
+use time::{util::days_in_month, Date, Duration, Month, Weekday};
+
+/// Shown after an expression that is not understood. Kept short to fit the prompt.
+const EXAMPLES: &str = "try 2021-03-04, last friday or 3 weeks ago";
+
+/// A step that relative expressions move by.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+fn unit(word: &str) -> Option<Unit> {
+    match word.strip_suffix('s').unwrap_or(word) {
+        "day" => Some(Unit::Day),
+        "week" => Some(Unit::Week),
+        "month" => Some(Unit::Month),
+        "year" => Some(Unit::Year),
+        _ => None,
+    }
+}
+
+/// Reads a weekday from its full name or its first three letters.
+fn weekday(word: &str) -> Option<Weekday> {
+    let mut day = Weekday::Monday;
+    for _ in 0..7 {
+        let name = day.to_string().to_lowercase();
+        if word == name || (word.len() >= 3 && name.starts_with(word)) {
+            return Some(day);
+        }
+        day = day.next();
+    }
+    None
+}
+
+/// Reads a count such as `3`, `a` or `an`. Counts too large for any date are rejected before
+/// they can overflow a `Duration`.
+fn count(word: &str) -> Option<i64> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        _ => word.parse().ok().filter(|n| *n <= 10_000_000),
+    }
+}
+
+/// Moves `date` by `n` months, keeping the day of the month where it exists and using the last
+/// day of the month otherwise.
+fn add_months(date: Date, n: i64) -> Option<Date> {
+    let index = date.year() as i64 * 12 + date.month() as i64 - 1 + n;
+    let year = i32::try_from(index.div_euclid(12)).ok()?;
+    let month = Month::try_from(index.rem_euclid(12) as u8 + 1).ok()?;
+    let day = date.day().min(days_in_month(month, year));
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+/// Moves `date` by `n` of `unit`, going back in time for negative `n`.
+fn shift(date: Date, unit: Unit, n: i64) -> Option<Date> {
+    match unit {
+        Unit::Day => date.checked_add(Duration::days(n)),
+        Unit::Week => date.checked_add(Duration::weeks(n)),
+        Unit::Month => add_months(date, n),
+        Unit::Year => add_months(date, n.checked_mul(12)?),
+    }
+}
+
+/// Returns the closest `day` before `date`, or after it when `forward` is set. The date itself
+/// is never returned.
+fn nearest(date: Date, day: Weekday, forward: bool) -> Option<Date> {
+    let mut date = date;
+    loop {
+        date = if forward {
+            date.next_day()?
+        } else {
+            date.previous_day()?
+        };
+        if date.weekday() == day {
+            return Some(date);
+        }
+    }
+}
+
+/// Returns true for dates the calendar can show: the first and last representable years are
+/// left out, since the month and week views around a date reach into the years beside it.
+fn in_range(date: Date) -> bool {
+    Date::MIN.year() < date.year() && date.year() < Date::MAX.year()
+}
+
+/// Reads a date from `text`, relative to `today`.
+///
+/// Accepts an absolute `YYYY-MM-DD` date, `today`, `yesterday` and `tomorrow`, a weekday (the
+/// latest one, today included), `last` or `next` followed by a weekday or by `week`, `month` or
+/// `year`, and counts such as `3 weeks ago` or `in 2 days`. Returns a message for the user when
+/// the text is not understood or names a date the calendar cannot show.
+pub fn parse(text: &str, today: Date) -> Result<Date, String> {
+    let text = text.trim().to_lowercase();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let date = match words.as_slice() {
+        [] => return Err(EXAMPLES.to_string()),
+        [word] if word.starts_with(|c: char| c.is_ascii_digit()) => {
+            let date = journal::parse_date(word)
+                .ok_or_else(|| "not a date in the form YYYY-MM-DD".to_string())?;
+            Some(date)
+        }
+        ["today"] | ["now"] => Some(today),
+        ["yesterday"] => today.previous_day(),
+        ["tomorrow"] => today.next_day(),
+        [day] if weekday(day).is_some() => {
+            let day = weekday(day).unwrap();
+            if today.weekday() == day {
+                Some(today)
+            } else {
+                nearest(today, day, false)
+            }
+        }
+        [direction @ ("last" | "next"), word] => {
+            let forward = *direction == "next";
+            if let Some(day) = weekday(word) {
+                nearest(today, day, forward)
+            } else if let Some(unit) = unit(word).filter(|_| !word.ends_with('s')) {
+                shift(today, unit, if forward { 1 } else { -1 })
+            } else {
+                return Err("expected a weekday, week, month or year".to_string());
+            }
+        }
+        [n, word, "ago"] | ["in", n, word] => {
+            let (Some(n), Some(unit)) = (count(n), unit(word)) else {
+                return Err(EXAMPLES.to_string());
+            };
+            let n = if words[0] == "in" { n } else { -n };
+            shift(today, unit, n)
+        }
+        _ => return Err(EXAMPLES.to_string()),
+    };
+    date.filter(|date| in_range(*date))
+        .ok_or_else(|| "that date is out of range".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> Date {
+        journal::parse_date(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        // a Wednesday
+        let today = date("2024-03-13");
+        let cases = [
+            ("2021-03-04", date("2021-03-04")),
+            (" Today ", today),
+            ("yesterday", date("2024-03-12")),
+            ("tomorrow", date("2024-03-14")),
+            ("monday", date("2024-03-11")),
+            ("wed", today),
+            ("last friday", date("2024-03-08")),
+            ("next wednesday", date("2024-03-20")),
+            ("last week", date("2024-03-06")),
+            ("next month", date("2024-04-13")),
+            ("last year", date("2023-03-13")),
+            ("3 weeks ago", date("2024-02-21")),
+            ("a month ago", date("2024-02-13")),
+            ("in 2 days", date("2024-03-15")),
+            ("15 months ago", date("2022-12-13")),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(parse(text, today), Ok(expected), "{}", text);
+        }
+
+        // the day is clamped to the end of shorter months
+        let end = date("2024-01-31");
+        assert_eq!(parse("next month", end), Ok(date("2024-02-29")));
+        assert_eq!(
+            parse("4 years ago", date("2024-02-29")),
+            Ok(date("2020-02-29"))
+        );
+        assert_eq!(
+            parse("1 year ago", date("2024-02-29")),
+            Ok(date("2023-02-28"))
+        );
+
+        for text in [
+            "",
+            "2021-02-30",
+            "someday",
+            "last weeks",
+            "x days ago",
+            "next",
+            "99999999999 days ago",
+        ] {
+            assert!(parse(text, today).is_err(), "{}", text);
+        }
+
+        // the calendar stops short of the last representable year
+        let out_of_range = Err("that date is out of range".to_string());
+        assert_eq!(parse("9999-12-01", today), out_of_range);
+        assert_eq!(parse("next year", date("9998-06-01")), out_of_range);
+        assert_eq!(parse("in 8000 years", today), out_of_range);
+        assert_eq!(parse("9998-12-31", today), Ok(date("9998-12-31")));
+    }
+}
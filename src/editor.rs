@@ -1,10 +1,13 @@
-use crate::app::AppState;
+use crate::app::{AppState, Mode};
+use crate::highlight::{code_block_lines, display_column, highlight_line, render_line};
+use crate::journal;
+use crate::markdown;
+use crate::onthisday::{draw_side_panels, PANEL_WIDTH};
+use crate::tracker::{draw_tracker, Tracker};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::prelude::Frame;
-use ratatui::style::{Color, Style};
-use ratatui::widgets::{Block, BorderType, Borders};
-use std::fs::{self, File, OpenOptions};
-use std::io::{self, BufRead, ErrorKind, Write};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use std::io;
 use std::str::FromStr;
 use tui_textarea::Input;
 
@@ -13,70 +16,69 @@ use tui_textarea::Input;
 /// # Params
 ///
 /// * `app` - Mutable reference to the application state.
-/// * `frame` - Mutable reference to the current UI frame.
-/// * `editor_area` - The area of the UI where the editor is displayed.
 ///
 /// # Returns
 ///
 /// Result<(), Box<dyn std::error::Error>>
-pub fn initialize_editor<'a>(
-    app: &'a mut AppState,
-    frame: &mut Frame,
-    editor_area: Rect,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub fn initialize_editor(app: &mut AppState) -> Result<(), Box<dyn std::error::Error>> {
     app.initialized();
     slog::info!(app.logger, "App Initialized");
 
-    let file = match get_entry_file_handle(app) {
-        Ok(file) => file,
+    let text = read_entry_text(app)?;
+    app.tracker = match journal::read_meta(&app.entries_dir, app.selected_date, app.vault.as_ref())
+    {
+        Ok(meta) => Tracker::new(meta),
         Err(e) => {
-            slog::error!(
-                app.logger,
-                "Failed to create or open file while initializing editor: {}",
-                e
-            );
-            panic!();
-            // return Err(Box::new(e));
+            slog::error!(app.logger, "Failed to read entry metadata: {}", e);
+            Tracker {
+                unreadable: true,
+                ..Tracker::default()
+            }
         }
     };
-    // file.sync_all()?;
-    read_file_to_editor(app, file)?;
+    app.editor = tui_textarea::TextArea::new(text.lines().map(String::from).collect());
     app.editor_text = app.editor.lines().concat();
-    slog::info!(app.logger, "Inserted file contents into editor"; "file" => %app.editor_text);
+    slog::info!(app.logger, "Inserted file contents into editor"; "length" => app.editor_text.len());
     app.editor.move_cursor(tui_textarea::CursorMove::Bottom);
-    let widget = app.editor.widget();
-    frame.render_widget(widget, editor_area);
     Ok(())
 }
 
-/// Reads the contents of a file and sets the editor's content.
+/// Reads the entry for the selected date, starting a new one with a heading if there is none.
 ///
 /// # Params
 ///
 /// * `app` - Mutable reference to the application state.
-/// * `file` - The file handle from which to read.
 ///
 /// # Returns
 ///
-/// Result<(), Box<dyn std::error::Error>>
-fn read_file_to_editor(app: &mut AppState, file: File) -> Result<(), Box<dyn std::error::Error>> {
-    let reader = io::BufReader::new(file);
-    let lines = match reader.lines().collect::<io::Result<Vec<String>>>() {
-        Ok(lines) => {
-            slog::info!(app.logger, "File lines read into editor");
-            lines
+/// io::Result<String>
+fn read_entry_text(app: &mut AppState) -> io::Result<String> {
+    match journal::read_entry(&app.entries_dir, app.selected_date, app.vault.as_ref()) {
+        Ok(Some(text)) => {
+            slog::info!(app.logger, "Opening existing entry"; "date" => %app.selected_date);
+            Ok(text)
+        }
+        Ok(None) => {
+            slog::info!(app.logger, "Starting new entry"; "date" => %app.selected_date);
+            Ok(format!("{}\n\n", journal::heading(app.selected_date)))
         }
         Err(e) => {
-            slog::error!(app.logger, "Failed to read lines from file: {}", e);
-            return Err(Box::new(e));
+            slog::error!(app.logger, "Failed to read entry"; "date" => %app.selected_date, "error" => %e);
+            Err(e)
         }
-    };
+    }
+}
 
-    // Create a new TextArea with the lines read from the file
-    let textarea = tui_textarea::TextArea::new(lines);
-    app.editor = textarea;
+/// Height of the title bar above the editor: a line of hints and the borders.
+const TITLE_HEIGHT: u16 = 3;
 
-    Ok(())
+/// Splits the screen into the title bar and the editor below it.
+pub fn split_screen(area: Rect) -> (Rect, Rect) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(TITLE_HEIGHT), Constraint::Min(0)])
+        .split(area);
+    (layout[0], layout[1])
 }
 
 /// Accepts a key input and pushes it to the editor.
@@ -104,119 +106,167 @@ pub fn update_editor(app: &mut AppState, input_key: Input) {
 ///
 /// void
 pub fn draw_editor(app: &mut AppState, frame: &mut Frame) {
-    let frame_size = frame.size();
+    let (_, mut editor_area) = split_screen(frame.size());
 
-    let constraints = vec![Constraint::Percentage(15), Constraint::Percentage(85)];
+    if app.tracker.visible {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(30)])
+            .split(editor_area);
+        editor_area = columns[0];
+        draw_tracker(&app.tracker, &app.theme, app.glyphs, frame, columns[1]);
+    }
 
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(frame_size);
+    if app.side_panels_shown() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(PANEL_WIDTH)])
+            .split(editor_area);
+        editor_area = columns[0];
+        draw_side_panels(app, frame, columns[1], "Alt-1-9", "Ctrl-F");
+    }
 
-    let editor_area = layout[1];
+    if app.preview {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(editor_area);
+        editor_area = columns[0];
+        draw_preview(app, frame, columns[1]);
+    }
 
     if !app.initialized {
-        let _result = match initialize_editor(app, frame, editor_area) {
-            Ok(()) => Ok(()),
-            Err(_e) => Err(()),
-        };
-    } else {
-        let none_string = "standard day".to_string();
-        let current_holiday = app
-            .holiday_info
-            .as_ref()
-            .and_then(|holiday_info| holiday_info.holidays.get(&app.selected_date))
-            .map(|holiday_name| (app.selected_date, holiday_name.clone()))
-            .unwrap_or((app.selected_date, none_string));
-
-        let _select_string: String = String::from_str("Selected Day ").unwrap();
-
-        let editor_block = match &*current_holiday.1 {
-            select_string if select_string == "Selected Day " => Block::default()
-                .title(format!(
-                    "『{}』☾ ﾟ｡⋆๑꒰⨳  ∟  ⨳๑ ꒱☁︎｡ﾟ｡。zｚℤＺ　",
-                    app.selected_date
-                ))
-                .border_style(Style::default().fg(Color::Rgb(255, 225, 120)))
-                .borders(Borders::all())
-                .border_type(BorderType::Double),
-            _ => Block::default()
-                .title(format!("happy {}", current_holiday.1))
-                .border_style(Style::default().fg(Color::Rgb(255, 255, 160)))
-                .borders(Borders::all())
-                .border_type(BorderType::Double),
-        };
-        app.editor.set_block(editor_block);
-        frame.render_widget(app.editor.widget(), editor_area);
+        if let Err(e) = initialize_editor(app) {
+            // leave without saving so an unreadable entry is never overwritten
+            slog::error!(app.logger, "Failed to initialize editor: {}", e);
+            app.initialized = false;
+            app.mode = Mode::CALENDAR;
+            return;
+        }
     }
+    let none_string = "standard day".to_string();
+    let current_holiday = app
+        .holiday_info
+        .as_ref()
+        .and_then(|holiday_info| holiday_info.holidays.get(&app.selected_date))
+        .map(|holiday_name| (app.selected_date, holiday_name.clone()))
+        .unwrap_or((app.selected_date, none_string));
+
+    let _select_string: String = String::from_str("Selected Day ").unwrap();
+
+    let editor_block = match &*current_holiday.1 {
+        "Selected Day " => Block::default()
+            .title(format!(
+                "{}{}{}{}",
+                app.glyphs.open, app.selected_date, app.glyphs.close, app.glyphs.dreaming
+            ))
+            .border_style(app.theme.editor_border)
+            .borders(Borders::all())
+            .border_set(app.glyphs.editor_border),
+        _ => Block::default()
+            .title(format!("happy {}", current_holiday.1))
+            .border_style(app.theme.holiday_border)
+            .borders(Borders::all())
+            .border_set(app.glyphs.editor_border),
+    };
+    draw_highlighted(app, frame, editor_area, editor_block);
 }
 
-/// Writes to a file the given text area's content.
+/// Draws the rendered Markdown of the entry being edited.
+///
+/// The preview scrolls in proportion to the cursor's position in the entry.
 ///
 /// # Params
 ///
-/// * `path` - The file system path to write to.
-/// * `textarea` - The text area containing lines of text to write.
+/// * `app` - Reference to the application state.
+/// * `frame` - Mutable reference to the current UI frame.
+/// * `area` - The area of the UI where the preview is displayed.
 ///
 /// # Returns
 ///
-/// io::Result<()>
-pub fn write_to_file(app: &mut AppState) -> io::Result<()> {
-    let editor_content = app.editor.lines().concat();
-    app.editor_text = editor_content.clone();
-    let mut file = get_entry_file_handle(app)?;
-    file.write_all(editor_content.as_bytes())?;
-    file.sync_all()?;
-    Ok(())
+/// void
+fn draw_preview(app: &AppState, frame: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title("preview")
+        .border_style(app.theme.panel_border)
+        .borders(Borders::all())
+        .border_set(app.glyphs.panel_border);
+    let inner = block.inner(area);
+    let text = markdown::render(
+        &app.editor.lines().join("\n"),
+        inner.width,
+        &app.theme.markdown,
+        app.glyphs,
+    );
+    let total_lines = app.editor.lines().len().max(1);
+    let rendered_lines = text.lines.len().saturating_sub(inner.height as usize);
+    let scroll = rendered_lines * app.editor.cursor().0 / total_lines;
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((scroll as u16, 0)),
+        area,
+    );
 }
 
-/// Creates a file for a selected date or opens one if it already exists.
+/// Renders the editor's text with Markdown highlighting, scrolled to keep the cursor visible.
+///
+/// Only the lines inside the viewport are highlighted, so long entries stay responsive.
 ///
 /// # Params
 ///
 /// * `app` - Mutable reference to the application state.
+/// * `frame` - Mutable reference to the current UI frame.
+/// * `area` - The area of the UI where the editor is displayed.
+/// * `block` - The block drawn around the editor.
 ///
 /// # Returns
 ///
-/// io::Result<File>
-fn get_entry_file_handle(app: &mut AppState) -> io::Result<File> {
-    let path = app
-        .entries_dir
-        .join(format!("{}_entry.md", app.selected_date));
-    match fs::metadata(&path) {
-        Ok(metadata) => {
-            if metadata.is_file() {
-                slog::info!(app.logger, "Opening existing file"; "path" => ?path);
-                OpenOptions::new().read(true).write(true).open(&path)
-            } else {
-                slog::error!(app.logger, "Path exists but is not a file"; "path" => ?path);
-                Err(io::Error::new(
-                    ErrorKind::Other,
-                    "Path exists but is not a file",
-                ))
-            }
-        }
-        Err(_e) => {
-            slog::info!(app.logger, "Creating new file"; "path" => ?path);
-            let mut file = OpenOptions::new().create(true).write(true).open(&path)?;
-            match file.write_all(format!("# Daily Entry - 『{}』\n", app.selected_date).as_bytes())
-            {
-                Ok(_) => {
-                    slog::info!(app.logger, "Inserted daily entry heading"; "date" => %app.selected_date);
-                    file.write_all(b"\n")?;
-                    file.sync_data()?;
-                    file.flush()?;
-                    slog::info!(app.logger, "File flushed"; "path" => ?path);
-                    // // Additional check to confirm file contents
-                    // let mut contents = String::new();
-                    // file.read_to_string(&mut contents)?;
-                    // slog::info!(app.logger, "File contents after write"; "contents" => %contents);
-                }
-                Err(e) => {
-                    slog::error!(app.logger, "Failed to write entry heading to file"; "error" => %e, "path" => ?path);
-                }
-            }
-            Ok(file)
-        }
-    }
+/// void
+fn draw_highlighted(app: &mut AppState, frame: &mut Frame, area: Rect, block: Block) {
+    let styles = app.theme.markdown;
+    let inner = block.inner(area);
+    let (width, height) = (inner.width as usize, inner.height as usize);
+    let lines = app.editor.lines();
+    let (cursor_row, cursor_col) = app.editor.cursor();
+    let cursor_column = display_column(&lines[cursor_row], cursor_col);
+    app.viewport
+        .follow((cursor_row, cursor_column), width, height);
+
+    let code_blocks = code_block_lines(lines);
+    let visible = lines
+        .iter()
+        .enumerate()
+        .skip(app.viewport.row)
+        .take(height)
+        .map(|(row, line)| {
+            let line_styles = highlight_line(line, code_blocks[row], &styles);
+            let cursor = (row == cursor_row).then_some((cursor_col, styles.cursor));
+            render_line(line, &line_styles, app.viewport.col, width, cursor)
+        })
+        .collect::<Vec<_>>();
+
+    frame.render_widget(Paragraph::new(visible).block(block), area);
+}
+
+/// Writes the editor's content to the entry file of the selected date, encrypting it when the
+/// journal is encrypted.
+///
+/// # Params
+///
+/// * `app` - Mutable reference to the application state.
+///
+/// # Returns
+///
+/// io::Result<()>
+pub fn write_to_file(app: &mut AppState) -> io::Result<()> {
+    let editor_content = app.editor.lines().join("\n");
+    app.editor_text = editor_content.clone();
+    journal::write_entry(
+        &app.entries_dir,
+        app.selected_date,
+        &editor_content,
+        app.vault.as_ref(),
+    )
 }
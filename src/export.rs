@@ -0,0 +1,440 @@
+use crate::calendar::holidays_of_year;
+use crate::highlight::code_block_lines;
+use crate::journal::{self, EntryMeta};
+use crate::vault::Vault;
+use pulldown_cmark::{html, Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::Path;
+use time::{Date, Month};
+
+/// An entry as it is exported.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ExportEntry {
+    pub date: Date,
+    pub body: String,
+    pub tags: BTreeSet<String>,
+    pub meta: EntryMeta,
+}
+
+/// Reads every entry between `from` and `to` (inclusive), oldest first.
+pub fn load_entries(
+    dir: &Path,
+    vault: Option<&Vault>,
+    from: Option<Date>,
+    to: Option<Date>,
+) -> io::Result<Vec<ExportEntry>> {
+    let mut entries = Vec::new();
+    for date in journal::entry_dates(dir) {
+        if from.is_some_and(|from| date < from) || to.is_some_and(|to| date > to) {
+            continue;
+        }
+        let body = journal::read_entry(dir, date, vault)?.unwrap_or_default();
+        entries.push(ExportEntry {
+            date,
+            tags: journal::tags(&body),
+            meta: journal::read_meta(dir, date, vault)?,
+            body,
+        });
+    }
+    Ok(entries)
+}
+
+/// Escapes text for use in HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders Markdown to HTML with the extensions the preview pane supports. HTML written in an
+/// entry is shown as text, so nothing in a shared site runs or hides content.
+fn markdown_to_html(markdown: &str) -> String {
+    let mut out = String::new();
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        event => event,
+    });
+    html::push_html(&mut out, events);
+    out
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+nav { display: flex; justify-content: space-between; margin: 1em 0; }
+.months { display: flex; flex-wrap: wrap; gap: 1.5em; }
+.months table { border-collapse: collapse; }
+.months td, .months th { width: 2em; text-align: center; }
+.holiday { background: #ffe9b0; }
+.season { background: #d9f2d9; }
+.tags a { margin-right: 0.5em; }
+.muted { color: #888; }
+";
+
+/// Wraps a page body in the shared HTML skeleton. `root` is the relative path to the site root.
+fn page(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n\
+         <body>\n<p><a href=\"{root}index.html\">daydream</a></p>\n{body}</body>\n</html>\n",
+        title = escape(title),
+    )
+}
+
+/// Holiday names of the given years, with their unicode decoration.
+fn holiday_names(years: &BTreeSet<i32>) -> HashMap<Date, (String, bool)> {
+    years
+        .iter()
+        .flat_map(|year| holidays_of_year(*year))
+        .map(|holiday| {
+            let name = format!("{} {}", holiday.name, holiday.unicode);
+            (holiday.date, (name, holiday.season))
+        })
+        .collect()
+}
+
+/// Returns the file name of a tag's page. Tags that differ only in case share a page, so they
+/// cannot overwrite each other on case-insensitive filesystems.
+fn tag_file(tag: &str) -> String {
+    format!("{}.html", tag.to_lowercase())
+}
+
+fn tag_links(tags: &BTreeSet<String>, root: &str) -> String {
+    let links: Vec<String> = tags
+        .iter()
+        .map(|tag| {
+            format!(
+                "<a href=\"{}tags/{}\">#{}</a>",
+                root,
+                escape(&tag_file(tag)),
+                escape(tag)
+            )
+        })
+        .collect();
+    format!("<p class=\"tags\">{}</p>\n", links.join(" "))
+}
+
+/// Renders one month as a table, linking days that have an entry.
+fn month_table(
+    year: i32,
+    month: Month,
+    dates: &BTreeSet<Date>,
+    holidays: &HashMap<Date, (String, bool)>,
+) -> String {
+    let Ok(first) = Date::from_calendar_date(year, month, 1) else {
+        return String::new();
+    };
+    let mut html = format!(
+        "<table>\n<caption>{}</caption>\n<tr><th>Su</th><th>Mo</th><th>Tu</th><th>We</th><th>Th</th><th>Fr</th><th>Sa</th></tr>\n<tr>",
+        month
+    );
+    let lead = first.weekday().number_days_from_sunday();
+    html.push_str(&"<td></td>".repeat(lead as usize));
+    let mut column = lead;
+    let mut date = Some(first);
+    while let Some(day) = date.filter(|day| day.month() == month) {
+        if column == 7 {
+            html.push_str("</tr>\n<tr>");
+            column = 0;
+        }
+        let (class, title) = match holidays.get(&day) {
+            Some((name, season)) => (
+                if *season {
+                    " class=\"season\""
+                } else {
+                    " class=\"holiday\""
+                },
+                format!(" title=\"{}\"", escape(name)),
+            ),
+            None => ("", String::new()),
+        };
+        let label = if dates.contains(&day) {
+            format!("<a href=\"entries/{}.html\">{}</a>", day, day.day())
+        } else {
+            day.day().to_string()
+        };
+        html.push_str(&format!("<td{}{}>{}</td>", class, title, label));
+        column += 1;
+        date = day.next_day();
+    }
+    html.push_str("</tr>\n</table>\n");
+    html
+}
+
+/// Writes a browsable site of the entries into `out`: an index, a calendar page per year, a page
+/// per entry with previous/next links, and a page per tag.
+pub fn html_site(entries: &[ExportEntry], out: &Path) -> io::Result<()> {
+    fs::create_dir_all(out.join("entries"))?;
+    fs::create_dir_all(out.join("tags"))?;
+    fs::write(out.join("style.css"), STYLE)?;
+
+    let dates: BTreeSet<Date> = entries.iter().map(|entry| entry.date).collect();
+    let years: BTreeSet<i32> = dates.iter().map(|date| date.year()).collect();
+    let holidays = holiday_names(&years);
+    // tags are grouped by their page, under the spelling seen first
+    let mut tags: BTreeMap<String, (&str, Vec<&ExportEntry>)> = BTreeMap::new();
+    for entry in entries {
+        for tag in &entry.tags {
+            let (_, tagged) = tags
+                .entry(tag_file(tag))
+                .or_insert_with(|| (tag, Vec::new()));
+            if tagged.last().map(|last| last.date) != Some(entry.date) {
+                tagged.push(entry);
+            }
+        }
+    }
+
+    for (i, entry) in entries.iter().enumerate() {
+        let link = |other: Option<&ExportEntry>, before: &str, after: &str| match other {
+            Some(other) => format!(
+                "<a href=\"{}.html\">{}{}{}</a>",
+                other.date, before, other.date, after
+            ),
+            None => "<span></span>".to_string(),
+        };
+        let nav = format!(
+            "<nav>{}<a href=\"../{}.html\">{}</a>{}</nav>\n",
+            link(i.checked_sub(1).and_then(|i| entries.get(i)), "&larr; ", ""),
+            entry.date.year(),
+            entry.date.year(),
+            link(entries.get(i + 1), "", " &rarr;"),
+        );
+        let mut body = nav.clone();
+        if let Some((name, _)) = holidays.get(&entry.date) {
+            body.push_str(&format!("<p class=\"muted\">{}</p>\n", escape(name)));
+        }
+        if !entry.tags.is_empty() {
+            body.push_str(&tag_links(&entry.tags, "../"));
+        }
+        body.push_str(&markdown_to_html(&entry.body));
+        body.push_str(&nav);
+        fs::write(
+            out.join("entries").join(format!("{}.html", entry.date)),
+            page(&entry.date.to_string(), "../", &body),
+        )?;
+    }
+
+    for year in &years {
+        let mut body = format!("<h1>{}</h1>\n<div class=\"months\">\n", year);
+        let mut month = Month::January;
+        loop {
+            body.push_str(&month_table(*year, month, &dates, &holidays));
+            if month == Month::December {
+                break;
+            }
+            month = month.next();
+        }
+        body.push_str("</div>\n");
+        fs::write(
+            out.join(format!("{}.html", year)),
+            page(&year.to_string(), "", &body),
+        )?;
+    }
+
+    for (file, (tag, tagged)) in &tags {
+        let mut body = format!("<h1>#{}</h1>\n<ul>\n", escape(tag));
+        for entry in tagged {
+            body.push_str(&format!(
+                "<li><a href=\"../entries/{}.html\">{}</a></li>\n",
+                entry.date, entry.date
+            ));
+        }
+        body.push_str("</ul>\n");
+        fs::write(
+            out.join("tags").join(file),
+            page(&format!("#{}", tag), "../", &body),
+        )?;
+    }
+
+    let mut body = String::from("<h1>daydream</h1>\n<h2>Years</h2>\n<ul>\n");
+    for year in &years {
+        let count = dates.iter().filter(|date| date.year() == *year).count();
+        body.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a> <span class=\"muted\">{} entries</span></li>\n",
+            year, year, count
+        ));
+    }
+    body.push_str("</ul>\n");
+    if !tags.is_empty() {
+        body.push_str("<h2>Tags</h2>\n");
+        let all: BTreeSet<String> = tags.values().map(|(tag, _)| tag.to_string()).collect();
+        body.push_str(&tag_links(&all, ""));
+    }
+    fs::write(out.join("index.html"), page("daydream", "", &body))
+}
+
+/// Pushes every ATX heading of an entry one level down, so it nests under the date heading.
+fn demote_headings(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let in_code = code_block_lines(&lines);
+    let mut demoted = String::new();
+    for (line, in_code) in lines.iter().zip(in_code) {
+        let level = line.chars().take_while(|c| *c == '#').count();
+        let is_heading =
+            (1..6).contains(&level) && line[level..].chars().next().is_none_or(char::is_whitespace);
+        if is_heading && !in_code {
+            demoted.push('#');
+        }
+        demoted.push_str(line);
+        demoted.push('\n');
+    }
+    demoted
+}
+
+/// Joins the entries into one Markdown document with a table of contents.
+pub fn markdown_book(entries: &[ExportEntry]) -> String {
+    let mut book = String::from("# Journal\n\n## Contents\n\n");
+    for entry in entries {
+        book.push_str(&format!("- [{}](#{})\n", entry.date, entry.date));
+    }
+    for entry in entries {
+        book.push_str(&format!("\n---\n\n## {}\n\n", entry.date));
+        book.push_str(&demote_headings(&entry.body));
+    }
+    book
+}
+
+/// An entry as it appears in the JSON export.
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    date: String,
+    tags: &'a BTreeSet<String>,
+    metadata: &'a EntryMeta,
+    body: &'a str,
+}
+
+/// Serializes the entries as a JSON array of objects with a date, tags, metadata and body.
+pub fn json_document(entries: &[ExportEntry]) -> io::Result<String> {
+    let entries: Vec<JsonEntry> = entries
+        .iter()
+        .map(|entry| JsonEntry {
+            date: entry.date.to_string(),
+            tags: &entry.tags,
+            metadata: &entry.meta,
+            body: &entry.body,
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&entries)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Strips the Markdown of an entry down to its text, keeping paragraphs and list items apart.
+fn markdown_to_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(&content),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::TaskListMarker(done) => text.push_str(if done { "[x] " } else { "[ ] " }),
+            Event::Start(Tag::Item) => text.push_str("- "),
+            Event::End(TagEnd::Item) => text.push('\n'),
+            Event::End(TagEnd::Paragraph)
+            | Event::End(TagEnd::Heading(_))
+            | Event::End(TagEnd::CodeBlock)
+            | Event::End(TagEnd::List(_)) => {
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push('\n');
+            }
+            Event::Rule => text.push_str("----\n\n"),
+            _ => {}
+        }
+    }
+    text.trim_end().to_string()
+}
+
+/// Joins the entries into one plain-text document, each under its date.
+pub fn plain_text(entries: &[ExportEntry]) -> String {
+    let mut document = String::new();
+    for entry in entries {
+        let date = entry.date.to_string();
+        document.push_str(&format!("{}\n{}\n\n", date, "=".repeat(date.len())));
+        document.push_str(&markdown_to_text(&entry.body));
+        document.push_str("\n\n\n");
+    }
+    document.truncate(document.trim_end().len());
+    document.push('\n');
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_site() {
+        let journal = tempfile::tempdir().unwrap();
+        let first = Date::from_calendar_date(2023, Month::December, 25).unwrap();
+        let second = Date::from_calendar_date(2024, Month::January, 2).unwrap();
+        journal::write_entry(journal.path(), first, "# Gifts\n#family <b>", None).unwrap();
+        journal::write_entry(journal.path(), second, "back to #work and #Work", None).unwrap();
+        let entries = load_entries(journal.path(), None, None, None).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let site = tempfile::tempdir().unwrap();
+        html_site(&entries, site.path()).unwrap();
+        let page = fs::read_to_string(site.path().join("entries/2023-12-25.html")).unwrap();
+        assert!(page.contains("Christmas Day"));
+        assert!(page.contains("<h1>Gifts</h1>"));
+        assert!(page.contains("&lt;b&gt;"));
+        assert!(!page.contains("<b>"));
+        assert!(page.contains("href=\"2024-01-02.html\""));
+        assert!(page.contains("href=\"../tags/family.html\""));
+        let year = fs::read_to_string(site.path().join("2024.html")).unwrap();
+        assert!(year.contains("<a href=\"entries/2024-01-02.html\">2</a>"));
+        let work = fs::read_to_string(site.path().join("tags/work.html")).unwrap();
+        assert_eq!(work.matches("2024-01-02.html").count(), 1);
+        let mut pages: Vec<String> = fs::read_dir(site.path().join("tags"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        pages.sort();
+        assert_eq!(pages, ["family.html", "work.html"]);
+        assert!(site.path().join("index.html").exists());
+    }
+
+    #[test]
+    fn test_documents() {
+        let date = Date::from_calendar_date(2024, Month::March, 5).unwrap();
+        let entries = [ExportEntry {
+            date,
+            body: "# Day\n\n- [x] walk #outside\n\n```\n# not a heading\n```\n".to_string(),
+            tags: BTreeSet::from(["outside".to_string()]),
+            meta: EntryMeta {
+                mood: Some(4),
+                ..EntryMeta::default()
+            },
+        }];
+
+        let book = markdown_book(&entries);
+        assert!(book.contains("- [2024-03-05](#2024-03-05)\n"));
+        assert!(book.contains("## 2024-03-05\n\n## Day\n"));
+        assert!(book.contains("\n# not a heading\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&json_document(&entries).unwrap()).unwrap();
+        assert_eq!(json[0]["date"], "2024-03-05");
+        assert_eq!(json[0]["tags"][0], "outside");
+        assert_eq!(json[0]["metadata"]["mood"], 4);
+
+        let text = plain_text(&entries);
+        assert_eq!(
+            text,
+            "2024-03-05\n==========\n\nDay\n\n- [x] walk #outside\n\n# not a heading\n"
+        );
+    }
+}
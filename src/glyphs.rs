@@ -0,0 +1,200 @@
+use ratatui::symbols::border;
+
+/// Decorative characters used by the interface, in a Unicode and a plain-ASCII flavour.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Glyphs {
+    pub ascii: bool,
+    /// Brackets around titles and dates, e.g. `『daydream』`.
+    pub open: &'static str,
+    pub close: &'static str,
+    /// Trailing decoration of the editor title.
+    pub dreaming: &'static str,
+    pub shrug: &'static str,
+    pub wave: &'static str,
+    pub enter: &'static str,
+    pub left_right: &'static str,
+    pub space: &'static str,
+    /// Filled and empty marks of the mood scale.
+    pub filled: &'static str,
+    pub empty: &'static str,
+    pub bullet: &'static str,
+    pub quote: &'static str,
+    pub rule: &'static str,
+    pub task_open: &'static str,
+    pub task_done: &'static str,
+    pub heading2: &'static str,
+    pub heading3: &'static str,
+    /// Border of the title bars.
+    pub title_border: border::Set,
+    /// Border of the editor.
+    pub editor_border: border::Set,
+    /// Border of calendar months and side panels.
+    pub panel_border: border::Set,
+}
+
+pub static UNICODE: Glyphs = Glyphs {
+    ascii: false,
+    open: "『",
+    close: "』",
+    dreaming: "☾ ﾟ｡⋆๑꒰⨳  ∟  ⨳๑ ꒱☁︎｡ﾟ｡。zｚℤＺ　",
+    shrug: "ﾉ(._.ﾉ)",
+    wave: "(/ ･〰･)／",
+    enter: "↵",
+    left_right: "←/→",
+    space: "␣",
+    filled: "● ",
+    empty: "○ ",
+    bullet: "• ",
+    quote: "│ ",
+    rule: "─",
+    task_open: "☐ ",
+    task_done: "☑ ",
+    heading2: "» ",
+    heading3: "› ",
+    title_border: border::QUADRANT_OUTSIDE,
+    editor_border: border::DOUBLE,
+    panel_border: border::ROUNDED,
+};
+
+pub static ASCII: Glyphs = Glyphs {
+    ascii: true,
+    open: "[ ",
+    close: " ]",
+    dreaming: " z z Z",
+    shrug: "(._.)",
+    wave: "\\(-_-)/",
+    enter: "Enter",
+    left_right: "Left/Right",
+    space: "Space",
+    filled: "* ",
+    empty: ". ",
+    bullet: "* ",
+    quote: "| ",
+    rule: "-",
+    task_open: "[ ] ",
+    task_done: "[x] ",
+    heading2: ">> ",
+    heading3: "> ",
+    title_border: ASCII_BORDER,
+    editor_border: border::Set {
+        horizontal_top: "=",
+        horizontal_bottom: "=",
+        ..ASCII_BORDER
+    },
+    panel_border: ASCII_BORDER,
+};
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+impl Glyphs {
+    /// Chooses between a Unicode decoration and its ASCII substitute.
+    pub fn pick(&self, unicode: &'static str, ascii: &'static str) -> &'static str {
+        if self.ascii {
+            ascii
+        } else {
+            unicode
+        }
+    }
+}
+
+/// Returns the glyphs for the given mode.
+pub fn for_mode(ascii: bool) -> &'static Glyphs {
+    if ascii {
+        &ASCII
+    } else {
+        &UNICODE
+    }
+}
+
+/// Decides whether the terminal is unlikely to render emoji and wide characters.
+pub fn detect_ascii() -> bool {
+    detect_ascii_from(|name| std::env::var(name).ok())
+}
+
+/// Looks at `TERM` and the locale through `var`.
+///
+/// The Linux console and other minimal terminals get ASCII, as does any locale that is set
+/// but is not UTF-8 (such as `C` or `POSIX`).
+pub fn detect_ascii_from(var: impl Fn(&str) -> Option<String>) -> bool {
+    let term = var("TERM").unwrap_or_default();
+    if ["linux", "vt100", "vt220", "dumb"].contains(&term.as_str()) {
+        return true;
+    }
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| var(name))
+        .find(|value| !value.is_empty());
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            !(locale.contains("utf-8") || locale.contains("utf8"))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unicode_width::UnicodeWidthStr;
+
+    #[test]
+    fn test_detect_ascii() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(detect_ascii_from(env(&[
+            ("TERM", "linux"),
+            ("LANG", "en_US.UTF-8")
+        ])));
+        assert!(detect_ascii_from(env(&[
+            ("LC_ALL", "C"),
+            ("LANG", "en_US.UTF-8")
+        ])));
+        assert!(!detect_ascii_from(env(&[
+            ("LC_ALL", ""),
+            ("LANG", "de_DE.utf8")
+        ])));
+        assert!(!detect_ascii_from(env(&[("TERM", "xterm-256color")])));
+    }
+
+    #[test]
+    fn test_ascii_glyphs_are_ascii() {
+        let glyphs = [
+            ASCII.open,
+            ASCII.close,
+            ASCII.dreaming,
+            ASCII.shrug,
+            ASCII.wave,
+            ASCII.enter,
+            ASCII.left_right,
+            ASCII.space,
+            ASCII.filled,
+            ASCII.empty,
+            ASCII.bullet,
+            ASCII.quote,
+            ASCII.rule,
+            ASCII.task_open,
+            ASCII.task_done,
+            ASCII.heading2,
+            ASCII.heading3,
+        ];
+        for glyph in glyphs {
+            assert!(glyph.is_ascii(), "{:?}", glyph);
+        }
+        assert_eq!(ASCII.filled.width(), ASCII.empty.width());
+    }
+}
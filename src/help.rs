@@ -0,0 +1,114 @@
+use crate::app::Mode;
+use crate::glyphs::Glyphs;
+use crate::prompt::centered;
+use crate::theme::Theme;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// Keys of the calendar, each with what it does.
+const CALENDAR_KEYS: [(&str, &str); 16] = [
+    (
+        "arrows",
+        "select a day; with `Ctrl`, 4 or 17 weeks at a time",
+    ),
+    ("Home End", "select the start or end of the week"),
+    ("g t", "go to a date, or to today"),
+    ("Enter", "read the selected day's entry"),
+    ("e", "edit it"),
+    ("b B", "bookmark the day, list bookmarks"),
+    ("p", "show this day in earlier years"),
+    ("l", "show entries linking to the day"),
+    ("1-9", "read an entry listed in those panels"),
+    ("f", "pick an attachment to open"),
+    ("v", "switch between year, quarter, month and week"),
+    ("o", "switch the calendar overlay"),
+    ("T", "switch theme"),
+    ("h", "list saved versions of the entry"),
+    ("L", "lock the screen"),
+    ("q Esc", "quit"),
+];
+
+/// Keys of the editor, each with what it does.
+const EDITOR_KEYS: [(&str, &str); 13] = [
+    ("Ctrl-S Esc", "save and go back to the calendar"),
+    ("Ctrl-C", "quit without saving"),
+    ("Ctrl-T", "record mood and habits"),
+    ("Ctrl-P", "toggle the rendered preview"),
+    ("Ctrl-O", "show this day in earlier years"),
+    ("Ctrl-B", "show entries linking here"),
+    ("Alt-1-9", "read an entry listed in those panels"),
+    ("Ctrl-G", "follow the [[link]] under the cursor"),
+    ("Ctrl-A", "attach a file at the cursor"),
+    ("Ctrl-F", "pick an attachment to open"),
+    ("Ctrl-R", "list saved versions of the entry"),
+    ("Ctrl-L", "save and lock the screen"),
+    ("F1", "show these keys"),
+];
+
+/// Keys of the viewer, each with what it does.
+const VIEWER_KEYS: [(&str, &str); 10] = [
+    ("arrows j k", "scroll"),
+    ("PgUp PgDn", "scroll a page"),
+    ("/ n N", "search, next and previous match"),
+    ("p", "show this day in earlier years"),
+    ("l", "show entries linking here"),
+    ("1-9", "read an entry listed in those panels"),
+    ("f", "pick an attachment to open"),
+    ("e", "edit this entry"),
+    ("L", "lock the screen"),
+    ("q Esc", "go back"),
+];
+
+/// Keys of the history view, each with what it does.
+const HISTORY_KEYS: [(&str, &str); 4] = [
+    ("arrows", "select a version"),
+    ("PgUp PgDn", "scroll its diff"),
+    ("Enter", "restore it"),
+    ("q Esc", "go back"),
+];
+
+/// Returns the keys of a screen, each with what it does.
+pub fn keys(mode: Mode) -> &'static [(&'static str, &'static str)] {
+    match mode {
+        Mode::CALENDAR => &CALENDAR_KEYS,
+        Mode::EDITOR => &EDITOR_KEYS,
+        Mode::VIEWER => &VIEWER_KEYS,
+        Mode::HISTORY => &HISTORY_KEYS,
+        Mode::SORT | Mode::LOCKED => &[],
+    }
+}
+
+/// Draws the keys of a screen in a box centred in `area`.
+pub fn draw_help(mode: Mode, theme: &Theme, glyphs: &Glyphs, frame: &mut Frame, area: Rect) {
+    let keys = keys(mode);
+    let key_width = keys.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = keys
+        .iter()
+        .map(|(key, action)| {
+            Line::from(vec![
+                Span::styled(format!("{:<1$}  ", key, key_width), theme.month_header),
+                Span::from(*action),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        "any key closes this",
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+
+    let area = centered(area, 70, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title("keys")
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        area,
+    );
+}
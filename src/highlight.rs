@@ -0,0 +1,339 @@
+use ratatui::prelude::*;
+use std::ops::Range;
+use unicode_width::UnicodeWidthChar;
+
+/// Styles applied to Markdown syntax in the editor.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MarkdownStyles {
+    pub heading: Style,
+    pub emphasis: Style,
+    pub strong: Style,
+    pub list_marker: Style,
+    pub code: Style,
+    pub link: Style,
+    pub checkbox: Style,
+    pub checkbox_done: Style,
+    pub tag: Style,
+    pub quote: Style,
+    pub cursor: Style,
+}
+
+impl Default for MarkdownStyles {
+    fn default() -> Self {
+        MarkdownStyles {
+            heading: Style::default()
+                .fg(Color::Rgb(255, 225, 120))
+                .add_modifier(Modifier::BOLD),
+            emphasis: Style::default().add_modifier(Modifier::ITALIC),
+            strong: Style::default().add_modifier(Modifier::BOLD),
+            list_marker: Style::default().fg(Color::LightGreen),
+            code: Style::default()
+                .fg(Color::LightRed)
+                .bg(Color::Rgb(40, 40, 40)),
+            link: Style::default()
+                .fg(Color::LightBlue)
+                .add_modifier(Modifier::UNDERLINED),
+            checkbox: Style::default().fg(Color::LightYellow),
+            checkbox_done: Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::CROSSED_OUT),
+            tag: Style::default().fg(Color::LightMagenta),
+            quote: Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::ITALIC),
+            cursor: Style::default().add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+/// First visible row and display column of the editor.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct Viewport {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Viewport {
+    /// Scrolls just enough to keep the cursor (row, display column) inside a `width` x `height` view.
+    pub fn follow(&mut self, cursor: (usize, usize), width: usize, height: usize) {
+        let (row, col) = cursor;
+        if row < self.row {
+            self.row = row;
+        } else if height > 0 && row >= self.row + height {
+            self.row = row + 1 - height;
+        }
+        if col < self.col {
+            self.col = col;
+        } else if width > 0 && col >= self.col + width {
+            self.col = col + 1 - width;
+        }
+    }
+}
+
+/// Returns true for lines that open or close a fenced code block.
+fn is_fence(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    line.len() - trimmed.len() < 4 && (trimmed.starts_with("```") || trimmed.starts_with("~~~"))
+}
+
+/// Marks every line that belongs to a fenced code block, fences included.
+///
+/// This is a single cheap pass so that only the visible lines need full highlighting.
+pub fn code_block_lines(lines: &[String]) -> Vec<bool> {
+    let mut inside = false;
+    lines
+        .iter()
+        .map(|line| {
+            if is_fence(line) {
+                inside = !inside;
+                true
+            } else {
+                inside
+            }
+        })
+        .collect()
+}
+
+/// Finds `#tag` ranges (in chars) within a line.
+pub fn tag_ranges(chars: &[char]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_word = i == 0 || chars[i - 1].is_whitespace();
+        if chars[i] == '#' && starts_word {
+            let end = (i + 1..chars.len())
+                .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '-' || chars[j] == '_'))
+                .unwrap_or(chars.len());
+            if end > i + 1 {
+                ranges.push(i..end);
+            }
+            i = end.max(i + 1);
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
+/// Finds `[[wiki link]]` ranges (in chars) within a line, brackets included.
+pub fn wiki_link_ranges(chars: &[char]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        if chars[i..].starts_with(&['[', '[']) {
+            if let Some(close) =
+                find_delim(chars, i + 2, &[']', ']']).filter(|close| *close > i + 2)
+            {
+                ranges.push(i..close + 2);
+                i = close + 2;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    ranges
+}
+
+/// Finds the closing `delim` starting at `from`, returning the index of its first char.
+fn find_delim(chars: &[char], from: usize, delim: &[char]) -> Option<usize> {
+    (from..chars.len().saturating_sub(delim.len() - 1)).find(|&j| chars[j..].starts_with(delim))
+}
+
+/// Styles the inline syntax of `chars[from..]`: code spans, emphasis, links, wiki links and tags.
+fn highlight_inline(chars: &[char], from: usize, styles: &MarkdownStyles, out: &mut [Style]) {
+    let mut i = from;
+    while i < chars.len() {
+        let rest = &chars[i..];
+        let span = if rest[0] == '`' {
+            find_delim(chars, i + 1, &['`']).map(|end| (end + 1, styles.code))
+        } else if rest.starts_with(&['*', '*']) || rest.starts_with(&['_', '_']) {
+            find_delim(chars, i + 2, &rest[..2]).map(|end| (end + 2, styles.strong))
+        } else if (rest[0] == '*' || rest[0] == '_' && (i == 0 || !chars[i - 1].is_alphanumeric()))
+            && rest.get(1).is_some_and(|c| !c.is_whitespace())
+        {
+            find_delim(chars, i + 1, &rest[..1]).map(|end| (end + 1, styles.emphasis))
+        } else if rest.starts_with(&['[', '[']) {
+            wiki_link_ranges(rest)
+                .first()
+                .filter(|range| range.start == 0)
+                .map(|range| (i + range.end, styles.link))
+        } else if rest[0] == '[' {
+            find_delim(chars, i + 1, &[']', '('])
+                .and_then(|close| find_delim(chars, close + 2, &[')']))
+                .map(|end| (end + 1, styles.link))
+        } else {
+            None
+        };
+        match span {
+            Some((end, style)) => {
+                for cell in &mut out[i..end] {
+                    *cell = cell.patch(style);
+                }
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    for range in tag_ranges(chars) {
+        for cell in &mut out[range] {
+            *cell = cell.patch(styles.tag);
+        }
+    }
+}
+
+/// Computes the style of every char of a line.
+pub fn highlight_line(line: &str, in_code_block: bool, styles: &MarkdownStyles) -> Vec<Style> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = vec![Style::default(); chars.len()];
+    if in_code_block {
+        out.fill(styles.code);
+        return out;
+    }
+
+    let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+    let body = &chars[indent..];
+    let hashes = body.iter().take_while(|c| **c == '#').count();
+    if (1..=6).contains(&hashes) && body.get(hashes) == Some(&' ') {
+        out.fill(styles.heading);
+        return out;
+    }
+    let rule: String = body.iter().filter(|c| !c.is_whitespace()).collect();
+    if rule.len() >= 3 && ["-", "*", "_"].iter().any(|c| rule == c.repeat(rule.len())) {
+        out.fill(styles.list_marker);
+        return out;
+    }
+
+    let mut inline_from = indent;
+    if body.first() == Some(&'>') {
+        out[indent..].fill(styles.quote);
+        inline_from += 1;
+    } else {
+        let digits = body.iter().take_while(|c| c.is_ascii_digit()).count();
+        let marker = if body.len() > 1 && "-*+".contains(body[0]) && body[1] == ' ' {
+            1
+        } else if digits > 0
+            && matches!(body.get(digits), Some('.') | Some(')'))
+            && body.get(digits + 1) == Some(&' ')
+        {
+            digits + 1
+        } else {
+            0
+        };
+        if marker > 0 {
+            out[indent..indent + marker].fill(styles.list_marker);
+            inline_from += marker;
+            let boxed = &chars[inline_from + 1..];
+            if boxed.len() >= 3
+                && boxed[0] == '['
+                && boxed[2] == ']'
+                && matches!(boxed[1], ' ' | 'x' | 'X')
+            {
+                let start = inline_from + 1;
+                out[start..start + 3].fill(styles.checkbox);
+                if boxed[1] != ' ' {
+                    out[start + 3..].fill(styles.checkbox_done);
+                }
+                inline_from = start + 3;
+            }
+        }
+    }
+    highlight_inline(&chars, inline_from, styles, &mut out);
+    out
+}
+
+/// Builds the visible part of a highlighted line, starting at display column `left`.
+///
+/// `cursor` is the char index to draw the cursor at, if it is on this line.
+pub fn render_line(
+    line: &str,
+    styles: &[Style],
+    left: usize,
+    width: usize,
+    cursor: Option<(usize, Style)>,
+) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut column = 0;
+    let mut push = |text: char, style: Style| match spans.last_mut() {
+        Some(span) if span.style == style => span.content.to_mut().push(text),
+        _ => spans.push(Span::styled(text.to_string(), style)),
+    };
+    for (i, (c, style)) in line.chars().zip(styles.iter()).enumerate() {
+        let char_width = c.width().unwrap_or(0);
+        if column >= left && column + char_width <= left + width {
+            let style = match cursor {
+                Some((at, cursor_style)) if at == i => style.patch(cursor_style),
+                _ => *style,
+            };
+            push(c, style);
+        }
+        column += char_width;
+    }
+    if let Some((at, cursor_style)) = cursor {
+        if at >= line.chars().count() && column >= left && column < left + width {
+            push(' ', cursor_style);
+        }
+    }
+    Line::from(spans)
+}
+
+/// Returns the display width of the first `chars` chars of a line.
+pub fn display_column(line: &str, chars: usize) -> usize {
+    line.chars()
+        .take(chars)
+        .map(|c| c.width().unwrap_or(0))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_line() {
+        let styles = MarkdownStyles::default();
+
+        let heading = highlight_line("## title", false, &styles);
+        assert!(heading.iter().all(|style| *style == styles.heading));
+
+        let task = highlight_line("- [x] done #chores", false, &styles);
+        assert_eq!(task[0], styles.list_marker);
+        assert_eq!(task[2], styles.checkbox);
+        assert!(task[6].add_modifier.contains(Modifier::CROSSED_OUT));
+        assert_eq!(task[13].fg, styles.tag.fg);
+
+        let inline = highlight_line("a `b` **c** [d](e) snake_case", false, &styles);
+        assert_eq!(inline[2], styles.code);
+        assert_eq!(inline[8], styles.strong);
+        assert_eq!(inline[12], styles.link);
+        assert_eq!(inline[25], Style::default());
+
+        let wiki = highlight_line("see [[2024-01-15]] and [[]]", false, &styles);
+        assert_eq!(wiki[4], styles.link);
+        assert_eq!(wiki[17], styles.link);
+        assert_eq!(wiki[18], Style::default());
+        assert_eq!(wiki[24], Style::default());
+
+        let lines: Vec<String> = ["```", "# not a heading", "```", "text"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(code_block_lines(&lines), vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn test_render_line_scrolls_and_draws_cursor() {
+        let styles = vec![Style::default(); 6];
+        let line = render_line("abcdef", &styles, 2, 3, Some((3, styles[0].reversed())));
+        let text: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "cde");
+        assert_eq!(line.spans[1].content, "d");
+
+        let mut viewport = Viewport::default();
+        viewport.follow((30, 4), 10, 20);
+        assert_eq!(viewport, Viewport { row: 11, col: 0 });
+    }
+}
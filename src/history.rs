@@ -0,0 +1,309 @@
+use crate::glyphs::Glyphs;
+use crate::journal::{self, ENTRY_SUFFIX};
+use crate::revlog;
+use crate::theme::Theme;
+use crate::vault::{self, Vault};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::process::Command;
+use time::Date;
+
+/// Where past versions of entries are kept.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    /// Commits of the git repository in the entries directory.
+    Git,
+    /// daydream's own revision logs, see `revlog`.
+    Revisions,
+}
+
+/// A past version of an entry.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Version {
+    /// Commit hash, or record number in the revision log.
+    pub id: String,
+    /// When the version was saved, e.g. `2024-01-15 21:04:11 +0100`.
+    pub time: String,
+    pub summary: String,
+}
+
+/// State of the history view for one date.
+#[derive(Clone, Debug)]
+pub struct History {
+    pub date: Date,
+    pub source: Source,
+    /// Whether the view was opened from the editor, which it returns to.
+    pub from_editor: bool,
+    /// Versions of the entry, newest first.
+    pub versions: Vec<Version>,
+    pub selected: usize,
+    /// Text of the entry as it is now.
+    pub current: String,
+    /// Text of the selected version, once loaded.
+    pub version_text: Option<String>,
+    /// Why the selected version could not be loaded.
+    pub error: Option<String>,
+    /// First line of the diff that is shown.
+    pub scroll: u16,
+}
+
+impl History {
+    /// Loads the versions and current text of the entry for a date.
+    pub fn open(
+        dir: &Path,
+        date: Date,
+        source: Source,
+        vault: Option<&Vault>,
+    ) -> io::Result<History> {
+        let versions = match source {
+            Source::Git => versions(dir, date)?,
+            Source::Revisions => revlog::list(dir, date)?
+                .into_iter()
+                .map(|(number, time)| Version {
+                    id: number.to_string(),
+                    time: revlog::format_time(time),
+                    summary: format!("revision {}", number + 1),
+                })
+                .collect(),
+        };
+        let mut history = History {
+            date,
+            source,
+            from_editor: false,
+            versions,
+            selected: 0,
+            current: journal::read_entry(dir, date, vault)?.unwrap_or_default(),
+            version_text: None,
+            error: None,
+            scroll: 0,
+        };
+        history.load(dir, vault);
+        Ok(history)
+    }
+
+    /// Returns the selected version, if there is one.
+    pub fn version(&self) -> Option<&Version> {
+        self.versions.get(self.selected)
+    }
+
+    /// Selects another version, `offset` places down the list, and loads its text.
+    pub fn select(&mut self, offset: isize, dir: &Path, vault: Option<&Vault>) {
+        let last = self.versions.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(offset).min(last);
+        self.scroll = 0;
+        self.load(dir, vault);
+    }
+
+    /// Reads and decrypts the text of the selected version.
+    fn load(&mut self, dir: &Path, vault: Option<&Vault>) {
+        let text = self.version().map(|version| match self.source {
+            Source::Git => {
+                let data = read_version(dir, &version.id, self.date)?;
+                let name = vault::sealed_name(&journal::entry_path(dir, self.date));
+                String::from_utf8(vault::decode(vault, &name, data)?)
+                    .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+            }
+            Source::Revisions => {
+                let number = version
+                    .id
+                    .parse()
+                    .map_err(|_| io::Error::new(ErrorKind::InvalidData, "invalid revision"))?;
+                revlog::read(dir, self.date, number, vault)
+            }
+        });
+        match text {
+            Some(Ok(text)) => (self.version_text, self.error) = (Some(text), None),
+            Some(Err(e)) => (self.version_text, self.error) = (None, Some(e.to_string())),
+            None => (self.version_text, self.error) = (None, None),
+        }
+    }
+}
+
+/// Runs git inside the journal directory and returns its standard output.
+fn git(dir: &Path, args: &[&str]) -> io::Result<Vec<u8>> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Returns true when the journal directory has its own repository.
+pub fn is_repository(dir: &Path) -> bool {
+    dir.join(".git").exists()
+}
+
+/// Creates the journal repository, with a local identity so commits never depend on the user's
+/// git configuration.
+pub fn init(dir: &Path) -> io::Result<()> {
+    if is_repository(dir) {
+        return Ok(());
+    }
+    fs::create_dir_all(dir)?;
+    git(dir, &["init", "--quiet"])?;
+    git(dir, &["config", "user.name", "daydream"])?;
+    git(dir, &["config", "user.email", "daydream@localhost"])?;
+    git(dir, &["config", "commit.gpgsign", "false"])?;
+    fs::write(dir.join(".gitignore"), "*.tmp\n")
+}
+
+/// Commits every change in the journal directory, returning false when nothing changed.
+///
+/// Files are committed exactly as they are on disk, so the history of an encrypted journal
+/// stays encrypted.
+pub fn commit(dir: &Path, message: &str) -> io::Result<bool> {
+    init(dir)?;
+    git(dir, &["add", "--all", "."])?;
+    let status = git(dir, &["status", "--porcelain"])?;
+    if status.is_empty() {
+        return Ok(false);
+    }
+    git(dir, &["commit", "--quiet", "--no-verify", "-m", message])?;
+    Ok(true)
+}
+
+/// Lists the commits that changed the entry for a date, newest first.
+pub fn versions(dir: &Path, date: Date) -> io::Result<Vec<Version>> {
+    if !is_repository(dir) {
+        return Ok(Vec::new());
+    }
+    let name = format!("{}{}", date, ENTRY_SUFFIX);
+    let log = git(dir, &["log", "--format=%H%x09%ci%x09%s", "--", &name])?;
+    Ok(String::from_utf8_lossy(&log)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            Some(Version {
+                id: fields.next()?.to_string(),
+                time: fields.next()?.to_string(),
+                summary: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Reads the entry file for a date as it was in a commit, still encrypted if it was then.
+pub fn read_version(dir: &Path, commit: &str, date: Date) -> io::Result<Vec<u8>> {
+    if commit.is_empty() || commit.starts_with('-') {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "invalid commit"));
+    }
+    git(
+        dir,
+        &["show", &format!("{}:{}{}", commit, date, ENTRY_SUFFIX)],
+    )
+}
+
+/// Returns a line-by-line diff turning `old` into `new`, with `+`/`-` markers.
+pub fn diff_lines(old: &str, new: &str, theme: &Theme) -> Vec<Line<'static>> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| {
+            let (marker, style) = match change.tag() {
+                ChangeTag::Delete => ("- ", theme.removed),
+                ChangeTag::Insert => ("+ ", theme.added),
+                ChangeTag::Equal => ("  ", Style::default()),
+            };
+            let text = change.value().trim_end_matches(['\r', '\n']);
+            Line::styled(format!("{}{}", marker, text), style)
+        })
+        .collect()
+}
+
+/// Draws the version list and the diff of the selected version against the current text.
+pub fn draw_history(
+    history: &History,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(40), Constraint::Min(20)])
+        .split(area);
+
+    let mut versions: Vec<Line> = history
+        .versions
+        .iter()
+        .map(|version| Line::from(format!("{}  {}", version.time, version.summary)))
+        .collect();
+    if versions.is_empty() {
+        versions.push(Line::styled(
+            "no saved versions",
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    } else if let Some(line) = versions.get_mut(history.selected) {
+        line.patch_style(Style::default().add_modifier(Modifier::REVERSED));
+    }
+    frame.render_widget(
+        Paragraph::new(versions).block(
+            Block::default()
+                .title(format!("{}{}{}", glyphs.open, history.date, glyphs.close))
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        columns[0],
+    );
+
+    let diff = match (&history.version_text, &history.error) {
+        (_, Some(error)) => vec![Line::from(error.clone())],
+        (Some(text), None) => diff_lines(text, &history.current, theme),
+        (None, None) => Vec::new(),
+    };
+    frame.render_widget(
+        Paragraph::new(diff).scroll((history.scroll, 0)).block(
+            Block::default()
+                .title("changes since this version")
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        columns[1],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_and_read_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let date = Date::from_calendar_date(2024, time::Month::March, 2).unwrap();
+        journal::write_entry(dir.path(), date, "first\n", None).unwrap();
+        assert!(commit(dir.path(), "first").unwrap());
+        assert!(!commit(dir.path(), "nothing changed").unwrap());
+        journal::write_entry(dir.path(), date, "second\n", None).unwrap();
+        assert!(commit(dir.path(), "second").unwrap());
+
+        let versions = versions(dir.path(), date).unwrap();
+        let summaries: Vec<&str> = versions.iter().map(|v| v.summary.as_str()).collect();
+        assert_eq!(summaries, ["second", "first"]);
+        let oldest = read_version(dir.path(), &versions[1].id, date).unwrap();
+        assert_eq!(oldest, b"first\n");
+
+        let diff = diff_lines("first\n", "second\n", &Theme::default());
+        let text: Vec<String> = diff
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(text, ["- first", "+ second"]);
+    }
+}
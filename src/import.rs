@@ -0,0 +1,502 @@
+use crate::journal;
+use crate::vault::Vault;
+use chrono::{Offset, TimeZone};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// A tool whose journal `daydream import` reads.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Source {
+    /// A plain-text jrnl journal, entries starting with `[YYYY-MM-DD HH:MM] Title.`
+    Jrnl,
+    /// The `Journal.json` of a Day One export.
+    DayOne,
+    /// A folder of Obsidian daily notes named `YYYY-MM-DD.md`.
+    Obsidian,
+}
+
+impl Source {
+    pub fn from_name(name: &str) -> Option<Source> {
+        match name {
+            "jrnl" => Some(Source::Jrnl),
+            "dayone" => Some(Source::DayOne),
+            "obsidian" => Some(Source::Obsidian),
+            _ => None,
+        }
+    }
+}
+
+/// One entry read from another tool.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Imported {
+    pub date: Date,
+    pub time: Option<Time>,
+    pub title: Option<String>,
+    pub body: String,
+    pub tags: BTreeSet<String>,
+}
+
+/// What importing does to one date.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    /// The date has no entry yet.
+    Create,
+    /// The imported text goes after the existing entry.
+    Append,
+    /// The existing entry already holds the imported text.
+    Unchanged,
+}
+
+/// The import of one date, ready to be written.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Planned {
+    pub date: Date,
+    pub action: Action,
+    /// Number of imported entries on this date.
+    pub entries: usize,
+    pub text: String,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Turns a tag from another tool into one daydream recognises, e.g. `road trip` into `road-trip`.
+fn clean_tag(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches(['#', '@'])
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '/' {
+                '-'
+            } else {
+                c
+            }
+        })
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+/// Parses `HH:MM`, `HH:MM:SS` and either with an `AM`/`PM` suffix.
+fn parse_time(text: &str) -> Option<Time> {
+    let text = text.trim();
+    let (clock, afternoon) = match text.get(text.len().saturating_sub(2)..) {
+        Some(suffix) if suffix.eq_ignore_ascii_case("am") => (&text[..text.len() - 2], Some(false)),
+        Some(suffix) if suffix.eq_ignore_ascii_case("pm") => (&text[..text.len() - 2], Some(true)),
+        _ => (text, None),
+    };
+    let mut parts = clock.trim().split(':');
+    let mut hour = parts.next()?.parse::<u8>().ok()?;
+    let minute = parts.next()?.parse::<u8>().ok()?;
+    let second = parts
+        .next()
+        .map_or(Some(0), |second| second.parse::<u8>().ok())?;
+    match afternoon {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    Time::from_hms(hour, minute, second).ok()
+}
+
+/// Rewrites jrnl's `@tags` as daydream `#tags`, collecting them into `tags`.
+fn convert_jrnl_tags(text: &str, tags: &mut BTreeSet<String>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut converted = String::with_capacity(text.len());
+    for (i, c) in chars.iter().enumerate() {
+        let starts_word = i == 0 || chars[i - 1].is_whitespace();
+        let next_is_tag = chars
+            .get(i + 1)
+            .is_some_and(|next| next.is_alphanumeric() || *next == '_');
+        if *c == '@' && starts_word && next_is_tag {
+            let tag: String = chars[i + 1..]
+                .iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '-' || **c == '_')
+                .collect();
+            tags.insert(tag);
+            converted.push('#');
+        } else {
+            converted.push(*c);
+        }
+    }
+    converted
+}
+
+/// Splits jrnl's first line into the title, its first sentence, and the rest of the body.
+fn split_title(line: &str) -> (String, String) {
+    let end = line
+        .char_indices()
+        .find(|(i, c)| matches!(c, '.' | '?' | '!') && line[i + 1..].starts_with(' '))
+        .map(|(i, _)| i + 1);
+    match end {
+        Some(end) => (line[..end].to_string(), line[end..].trim().to_string()),
+        None => (line.trim().to_string(), String::new()),
+    }
+}
+
+/// Reads a jrnl journal in its plain-text format.
+pub fn read_jrnl(text: &str) -> Vec<Imported> {
+    let mut imported: Vec<Imported> = Vec::new();
+    for line in text.lines() {
+        let header = line.strip_prefix('[').and_then(|rest| {
+            let (stamp, title) = rest.split_once(']')?;
+            let (date, time) = stamp.split_once(' ')?;
+            Some((journal::parse_date(date)?, parse_time(time)?, title))
+        });
+        match (header, imported.last_mut()) {
+            (Some((date, time, title)), _) => {
+                let mut tags = BTreeSet::new();
+                let title = title.trim().trim_end_matches(" *");
+                let (title, body) = split_title(&convert_jrnl_tags(title, &mut tags));
+                imported.push(Imported {
+                    date,
+                    time: Some(time),
+                    title: Some(title).filter(|title| !title.is_empty()),
+                    body,
+                    tags,
+                });
+            }
+            (None, Some(entry)) => {
+                let line = convert_jrnl_tags(line, &mut entry.tags);
+                if !entry.body.is_empty() || !line.trim().is_empty() {
+                    entry.body.push('\n');
+                    entry.body.push_str(&line);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    for entry in &mut imported {
+        entry.body = entry.body.trim().to_string();
+    }
+    imported
+}
+
+#[derive(Deserialize)]
+struct DayOneExport {
+    entries: Vec<DayOneEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOneEntry {
+    creation_date: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    time_zone: Option<String>,
+}
+
+/// Returns the offset from UTC of a named time zone, such as `Europe/Berlin`, at `instant`.
+fn zone_offset(zone: &str, instant: OffsetDateTime) -> Option<UtcOffset> {
+    let zone: Tz = zone.parse().ok()?;
+    let utc = chrono::DateTime::from_timestamp(instant.unix_timestamp(), 0)?;
+    let offset = zone.offset_from_utc_datetime(&utc.naive_utc()).fix();
+    UtcOffset::from_whole_seconds(offset.local_minus_utc()).ok()
+}
+
+/// Parses Day One's `YYYY-MM-DDTHH:MM:SSZ` creation dates, which are in UTC.
+fn parse_utc(text: &str) -> Option<PrimitiveDateTime> {
+    let (date, time) = text.trim_end_matches('Z').split_once('T')?;
+    Some(PrimitiveDateTime::new(
+        journal::parse_date(date)?,
+        parse_time(time)?,
+    ))
+}
+
+/// Reads the JSON of a Day One export. Entries are dated in the time zone they were written in,
+/// or, when the export does not name a known one, in the offset `local` gives for the moment
+/// they were written.
+pub fn read_day_one(
+    json: &str,
+    local: impl Fn(OffsetDateTime) -> UtcOffset,
+) -> io::Result<Vec<Imported>> {
+    let export: DayOneExport = serde_json::from_str(json)?;
+    export
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let created = parse_utc(&entry.creation_date)
+                .ok_or_else(|| invalid(format!("bad creation date `{}`", entry.creation_date)))?
+                .assume_utc();
+            let offset = entry
+                .time_zone
+                .as_deref()
+                .and_then(|zone| zone_offset(zone, created))
+                .unwrap_or_else(|| local(created));
+            let created = created.to_offset(offset);
+            Ok(Imported {
+                date: created.date(),
+                time: Some(created.time()),
+                title: None,
+                body: entry.text.trim().to_string(),
+                tags: entry.tags.iter().map(|tag| clean_tag(tag)).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Splits the YAML front matter off a note, returning the tags it lists and the rest of the note.
+fn split_front_matter(note: &str) -> (BTreeSet<String>, &str) {
+    let mut tags = BTreeSet::new();
+    let Some(rest) = note.strip_prefix("---\n") else {
+        return (tags, note);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (tags, note);
+    };
+    let mut in_tags = false;
+    for line in rest[..end].lines() {
+        if let Some(value) = line
+            .strip_prefix("tags:")
+            .or_else(|| line.strip_prefix("tag:"))
+        {
+            in_tags = true;
+            let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+            tags.extend(value.split([',', ' ']).map(clean_tag));
+        } else if let Some(item) = line.trim_start().strip_prefix("- ").filter(|_| in_tags) {
+            tags.insert(clean_tag(item));
+        } else {
+            in_tags = false;
+        }
+    }
+    tags.remove("");
+    let after = &rest[end + 4..];
+    let body = after.strip_prefix('\n').unwrap_or(after);
+    (tags, body)
+}
+
+/// Collects the Markdown files under `dir`, skipping hidden folders such as `.obsidian`.
+fn markdown_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if path.is_dir() && !hidden {
+            markdown_files(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "md") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Reads a folder of Obsidian daily notes. Other notes are left out; front matter tags are kept.
+pub fn read_obsidian(dir: &Path) -> io::Result<Vec<Imported>> {
+    let mut files = Vec::new();
+    markdown_files(dir, &mut files)?;
+    let mut imported = Vec::new();
+    for path in files {
+        let date = path
+            .file_stem()
+            .and_then(|stem| journal::parse_date(&stem.to_string_lossy()));
+        let Some(date) = date else {
+            continue;
+        };
+        let note = fs::read_to_string(&path)?;
+        let (tags, body) = split_front_matter(&note);
+        imported.push(Imported {
+            date,
+            time: None,
+            title: None,
+            body: body.trim().to_string(),
+            tags,
+        });
+    }
+    Ok(imported)
+}
+
+/// Reads the journal of another tool at `path`.
+pub fn read(source: Source, path: &Path) -> io::Result<Vec<Imported>> {
+    let local = |instant| UtcOffset::local_offset_at(instant).unwrap_or(UtcOffset::UTC);
+    match source {
+        Source::Jrnl => Ok(read_jrnl(&fs::read_to_string(path)?)),
+        Source::DayOne => read_day_one(&fs::read_to_string(path)?, local),
+        Source::Obsidian => read_obsidian(path),
+    }
+}
+
+/// Renders one imported entry as a section: a `## HH:MM Title` heading, the body, and a line
+/// with the tags the body does not mention itself.
+fn section(entry: &Imported) -> String {
+    let mut heading = Vec::new();
+    if let Some(time) = entry.time {
+        heading.push(format!("{:02}:{:02}", time.hour(), time.minute()));
+    }
+    heading.extend(entry.title.clone());
+    let mut text = String::new();
+    if !heading.is_empty() {
+        text.push_str(&format!("## {}\n\n", heading.join(" ")));
+    }
+    if !entry.body.is_empty() {
+        text.push_str(&entry.body);
+        text.push('\n');
+    }
+    let mentioned = journal::tags(&text);
+    let missing: Vec<String> = entry
+        .tags
+        .iter()
+        .filter(|tag| !tag.is_empty() && !mentioned.contains(*tag))
+        .map(|tag| format!("#{}", tag))
+        .collect();
+    if !missing.is_empty() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&missing.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+/// Works out what importing the entries does to each date, oldest first, without writing.
+///
+/// Entries of one date are joined in time order. A date that already has an entry gets the
+/// imported text appended, unless it already ends with it, so importing twice changes nothing.
+pub fn plan(imported: &[Imported], dir: &Path, vault: Option<&Vault>) -> io::Result<Vec<Planned>> {
+    let mut dates: BTreeMap<Date, Vec<&Imported>> = BTreeMap::new();
+    for entry in imported {
+        dates.entry(entry.date).or_default().push(entry);
+    }
+    let mut planned = Vec::new();
+    for (date, mut entries) in dates {
+        entries.sort_by_key(|entry| entry.time);
+        let sections: Vec<String> = entries.iter().map(|entry| section(entry)).collect();
+        let sections = sections.join("\n");
+        let (action, text) = match journal::read_entry(dir, date, vault)? {
+            None => (
+                Action::Create,
+                format!("{}\n\n{}", journal::heading(date), sections),
+            ),
+            Some(existing) if existing.trim_end().ends_with(sections.trim_end()) => {
+                (Action::Unchanged, existing)
+            }
+            Some(existing) => (
+                Action::Append,
+                format!("{}\n\n{}", existing.trim_end(), sections),
+            ),
+        };
+        planned.push(Planned {
+            date,
+            action,
+            entries: entries.len(),
+            text,
+        });
+    }
+    Ok(planned)
+}
+
+/// Writes the planned entries into the entries directory.
+pub fn apply(planned: &[Planned], dir: &Path, vault: Option<&Vault>) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    for planned in planned {
+        if planned.action != Action::Unchanged {
+            journal::write_entry(dir, planned.date, &planned.text, vault)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Month;
+
+    fn date(day: u8) -> Date {
+        Date::from_calendar_date(2024, Month::January, day).unwrap()
+    }
+
+    #[test]
+    fn test_read_formats() {
+        let jrnl = "[2024-01-15 09:30] Coffee with @sam. It was good.\nMore @work talk.\n\n\
+                    [2024-01-15 08:00 PM] Late walk *\n";
+        let entries = read_jrnl(jrnl);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title.as_deref(), Some("Coffee with #sam."));
+        assert_eq!(entries[0].body, "It was good.\nMore #work talk.");
+        assert_eq!(
+            entries[0].tags,
+            BTreeSet::from(["sam".into(), "work".into()])
+        );
+        assert_eq!(entries[1].time, Time::from_hms(20, 0, 0).ok());
+        assert_eq!(entries[1].title.as_deref(), Some("Late walk"));
+
+        let day_one = r#"{"entries": [{"creationDate": "2024-01-15T23:30:00Z",
+            "text": "Late", "tags": ["road trip"]},
+            {"creationDate": "2024-01-15T23:30:00Z", "timeZone": "Nowhere/Atlantis"},
+            {"creationDate": "2024-07-16T02:30:00Z", "timeZone": "America/New_York"}]}"#;
+        let local = |_| UtcOffset::from_hms(2, 0, 0).unwrap();
+        let entries = read_day_one(day_one, local).unwrap();
+        assert_eq!(entries[0].date, date(16));
+        assert_eq!(entries[0].time, Time::from_hms(1, 30, 0).ok());
+        assert_eq!(entries[0].tags, BTreeSet::from(["road-trip".into()]));
+        assert_eq!(entries[1].date, date(16));
+        // New York is four hours behind UTC in summer
+        assert_eq!(entries[2].date, journal::parse_date("2024-07-15").unwrap());
+        assert_eq!(entries[2].time, Time::from_hms(22, 30, 0).ok());
+
+        let notes = tempfile::tempdir().unwrap();
+        fs::create_dir_all(notes.path().join("Daily/.obsidian")).unwrap();
+        let note = "---\ntags:\n  - health\n  - a/b\n---\n# Monday\n";
+        fs::write(notes.path().join("Daily/2024-01-15.md"), note).unwrap();
+        fs::write(notes.path().join("Ideas.md"), "not a daily note").unwrap();
+        let entries = read_obsidian(notes.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].body, "# Monday");
+        assert_eq!(
+            entries[0].tags,
+            BTreeSet::from(["a-b".into(), "health".into()])
+        );
+
+        let (tags, body) = split_front_matter("---\ntags: x\n---\n- item\n");
+        assert_eq!(tags, BTreeSet::from(["x".into()]));
+        assert_eq!(body, "- item\n");
+    }
+
+    #[test]
+    fn test_plan_and_apply() {
+        let dir = tempfile::tempdir().unwrap();
+        journal::write_entry(dir.path(), date(2), "# Mine\n", None).unwrap();
+        let imported = [
+            Imported {
+                date: date(2),
+                time: Time::from_hms(18, 0, 0).ok(),
+                title: Some("Evening".to_string()),
+                body: "Tired.".to_string(),
+                tags: BTreeSet::from(["rest".to_string()]),
+            },
+            Imported {
+                date: date(1),
+                time: None,
+                title: None,
+                body: "New year".to_string(),
+                tags: BTreeSet::new(),
+            },
+        ];
+        let planned = plan(&imported, dir.path(), None).unwrap();
+        assert_eq!(planned[0].action, Action::Create);
+        assert_eq!(
+            planned[0].text,
+            "# Daily Entry - 『2024-01-01』\n\nNew year\n"
+        );
+        assert_eq!(planned[1].action, Action::Append);
+        assert_eq!(
+            planned[1].text,
+            "# Mine\n\n## 18:00 Evening\n\nTired.\n\n#rest\n"
+        );
+
+        apply(&planned, dir.path(), None).unwrap();
+        let again = plan(&imported, dir.path(), None).unwrap();
+        assert!(again
+            .iter()
+            .all(|planned| planned.action == Action::Unchanged));
+    }
+}
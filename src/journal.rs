@@ -0,0 +1,263 @@
+use crate::bookmarks::{self, Bookmarks, BOOKMARKS_FILE};
+use crate::highlight::{code_block_lines, tag_ranges};
+use crate::links::Backlinks;
+use crate::vault::{self, Vault};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use time::{Date, Month};
+
+/// Directory, relative to the working directory, that holds the journal.
+pub const ENTRIES_DIR: &str = "entries";
+
+/// Suffix appended to the date for every entry file, e.g. `2024-01-15_entry.md`.
+pub const ENTRY_SUFFIX: &str = "_entry.md";
+
+/// Suffix appended to the date for an entry's metadata sidecar, e.g. `2024-01-15_meta.toml`.
+pub const META_SUFFIX: &str = "_meta.toml";
+
+/// Per-day data recorded alongside an entry.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+pub struct EntryMeta {
+    /// Mood score from 1 (low) to 5 (high).
+    pub mood: Option<u8>,
+    /// Habit name mapped to whether it was done that day.
+    #[serde(default)]
+    pub habits: BTreeMap<String, bool>,
+}
+
+impl EntryMeta {
+    /// Returns true when nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.mood.is_none() && !self.habits.values().any(|done| *done)
+    }
+
+    /// Returns whether the named habit was done.
+    pub fn habit(&self, name: &str) -> bool {
+        self.habits.get(name).copied().unwrap_or(false)
+    }
+}
+
+/// Everything known about the journal on disk, cached between frames.
+#[derive(Default, Clone, Debug)]
+pub struct JournalIndex {
+    /// Dates that have an entry file.
+    pub dates: BTreeSet<Date>,
+    /// Metadata for every date that has a sidecar.
+    pub meta: HashMap<Date, EntryMeta>,
+    /// Previews of the entries read so far, filled in as the calendar shows them.
+    pub previews: HashMap<Date, String>,
+    /// Named days, from the bookmarks file.
+    pub bookmarks: Bookmarks,
+    /// Entries linking to each date, read the first time backlinks are asked for.
+    pub backlinks: Option<Backlinks>,
+    /// Names of the files attached to the dates looked at so far.
+    pub attachments: HashMap<Date, Vec<String>>,
+}
+
+/// Returns the path of the entry file for a date.
+pub fn entry_path(dir: &Path, date: Date) -> PathBuf {
+    dir.join(format!("{}{}", date, ENTRY_SUFFIX))
+}
+
+/// Returns the path of the metadata sidecar for a date.
+pub fn meta_path(dir: &Path, date: Date) -> PathBuf {
+    dir.join(format!("{}{}", date, META_SUFFIX))
+}
+
+/// Returns the heading every new entry starts with. It is written the same whatever the display
+/// mode, and only rendered with the mode's brackets.
+pub fn heading(date: Date) -> String {
+    format!("# Daily Entry - 『{}』", date)
+}
+
+/// Parses a `YYYY-MM-DD` date.
+pub fn parse_date(text: &str) -> Option<Date> {
+    let mut parts = text.splitn(3, '-');
+    let year = parts.next()?.parse::<i32>().ok()?;
+    let month = Month::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    let day = parts.next()?.parse::<u8>().ok()?;
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+/// Extracts the date from an entry file name, ignoring any other file.
+pub fn parse_entry_name(name: &str) -> Option<Date> {
+    parse_date(name.strip_suffix(ENTRY_SUFFIX)?)
+}
+
+/// Lists every date with an entry file, oldest first.
+pub fn entry_dates(dir: &Path) -> Vec<Date> {
+    let mut dates: Vec<Date> = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| parse_entry_name(&entry.file_name().to_string_lossy()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    dates.sort();
+    dates
+}
+
+/// Collects the `#tags` of an entry, without the `#`, ignoring code blocks.
+pub fn tags(text: &str) -> BTreeSet<String> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let in_code = code_block_lines(&lines);
+    lines
+        .iter()
+        .zip(in_code)
+        .filter(|(_, in_code)| !in_code)
+        .flat_map(|(line, _)| {
+            let chars: Vec<char> = line.chars().collect();
+            tag_ranges(&chars)
+                .into_iter()
+                .map(|range| chars[range.start + 1..range.end].iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Condenses an entry into one line of text for previews, dropping Markdown markers, code
+/// blocks and the `Daily Entry` heading every new entry starts with.
+pub fn preview(text: &str) -> String {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let in_code = code_block_lines(&lines);
+    let words: Vec<&str> = lines
+        .iter()
+        .zip(in_code)
+        .filter(|(line, in_code)| !in_code && !line.starts_with("# Daily Entry - "))
+        .map(|(line, _)| {
+            line.trim()
+                .trim_start_matches(['#', '>', '-', '*', '+', ' '])
+                .trim_start_matches("[ ] ")
+                .trim_start_matches("[x] ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect();
+    words.join(" ")
+}
+
+/// Returns true for the files that hold journal data and are encrypted with it.
+pub fn is_journal_file(name: &str) -> bool {
+    name.ends_with(ENTRY_SUFFIX) || name.ends_with(META_SUFFIX) || name == BOOKMARKS_FILE
+}
+
+/// Writes a file by renaming a fully written temporary file over it.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
+
+/// Reads a journal file as text, decrypting it when needed. Returns `None` if it does not exist.
+pub fn read_text(path: &Path, vault: Option<&Vault>) -> io::Result<Option<String>> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let plain = vault::decode(vault, &vault::sealed_name(path), data)?;
+    String::from_utf8(plain)
+        .map(Some)
+        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Writes a journal file as text, encrypting it when the journal is encrypted.
+pub fn write_text(path: &Path, text: &str, vault: Option<&Vault>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let data = vault::encode(vault, &vault::sealed_name(path), text.as_bytes())?;
+    write_atomic(path, &data)
+}
+
+/// Reads the entry for a date, or `None` if it has not been written.
+pub fn read_entry(dir: &Path, date: Date, vault: Option<&Vault>) -> io::Result<Option<String>> {
+    read_text(&entry_path(dir, date), vault)
+}
+
+/// Writes the entry for a date.
+pub fn write_entry(dir: &Path, date: Date, text: &str, vault: Option<&Vault>) -> io::Result<()> {
+    write_text(&entry_path(dir, date), text, vault)
+}
+
+/// Reads the metadata sidecar for a date, returning empty metadata if there is none.
+pub fn read_meta(dir: &Path, date: Date, vault: Option<&Vault>) -> io::Result<EntryMeta> {
+    match read_text(&meta_path(dir, date), vault)? {
+        Some(text) => toml::from_str(&text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e)),
+        None => Ok(EntryMeta::default()),
+    }
+}
+
+/// Writes the metadata sidecar for a date, removing it when nothing is recorded.
+pub fn write_meta(
+    dir: &Path,
+    date: Date,
+    meta: &EntryMeta,
+    vault: Option<&Vault>,
+) -> io::Result<()> {
+    let path = meta_path(dir, date);
+    if meta.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    let text = toml::to_string(meta).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    write_text(&path, &text, vault)
+}
+
+/// Scans the journal directory for entries, their metadata and the bookmarks.
+pub fn build_index(dir: &Path, vault: Option<&Vault>) -> JournalIndex {
+    let dates: BTreeSet<Date> = entry_dates(dir).into_iter().collect();
+    let meta = dates
+        .iter()
+        .filter_map(|date| match read_meta(dir, *date, vault) {
+            Ok(meta) if !meta.is_empty() => Some((*date, meta)),
+            _ => None,
+        })
+        .collect();
+    JournalIndex {
+        dates,
+        meta,
+        previews: HashMap::new(),
+        bookmarks: bookmarks::read(dir, vault).unwrap_or_default(),
+        backlinks: None,
+        attachments: HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meta_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let date = parse_date("2024-03-13").unwrap();
+        assert_eq!(
+            read_meta(dir.path(), date, None).unwrap(),
+            EntryMeta::default()
+        );
+
+        let mut meta = EntryMeta {
+            mood: Some(4),
+            ..EntryMeta::default()
+        };
+        meta.habits.insert("reading".to_string(), true);
+        write_meta(dir.path(), date, &meta, None).unwrap();
+        assert_eq!(read_meta(dir.path(), date, None).unwrap(), meta);
+
+        // nothing recorded removes the sidecar rather than writing an empty one
+        write_meta(dir.path(), date, &EntryMeta::default(), None).unwrap();
+        assert!(!meta_path(dir.path(), date).exists());
+
+        fs::write(meta_path(dir.path(), date), "mood = \"high\"").unwrap();
+        let error = read_meta(dir.path(), date, None).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::app::AppState;
+use crate::dateexpr;
+use crate::highlight::{code_block_lines, wiki_link_ranges};
+use crate::journal;
+use crate::vault::Vault;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use time::Date;
+
+/// Entries linking to each date, keyed by the date linked to.
+pub type Backlinks = HashMap<Date, BTreeSet<Date>>;
+
+/// Resolves the inside of a `[[...]]` link written in the entry on `date`. Anything after a `|`
+/// is the link's label, so `[[2024-01-15|the trip]]` and `[[yesterday]]` both name a day.
+pub fn resolve(target: &str, date: Date) -> Option<Date> {
+    let target = target.split('|').next().unwrap_or_default();
+    dateexpr::parse(target.trim(), date).ok()
+}
+
+/// Returns the dates an entry written on `date` links to, leaving out code blocks and itself.
+pub fn targets(text: &str, date: Date) -> BTreeSet<Date> {
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let in_code = code_block_lines(&lines);
+    let mut targets = BTreeSet::new();
+    for (line, _) in lines.iter().zip(in_code).filter(|(_, code)| !code) {
+        let chars: Vec<char> = line.chars().collect();
+        for range in wiki_link_ranges(&chars) {
+            let inner: String = chars[range.start + 2..range.end - 2].iter().collect();
+            targets.extend(resolve(&inner, date).filter(|target| *target != date));
+        }
+    }
+    targets
+}
+
+/// Returns the date of the link at char `column` of line `row`, brackets included.
+pub fn link_at(lines: &[String], row: usize, column: usize, date: Date) -> Option<Date> {
+    if *code_block_lines(lines).get(row)? {
+        return None;
+    }
+    let chars: Vec<char> = lines[row].chars().collect();
+    let range = wiki_link_ranges(&chars)
+        .into_iter()
+        .find(|range| range.contains(&column))?;
+    let inner: String = chars[range.start + 2..range.end - 2].iter().collect();
+    resolve(&inner, date)
+}
+
+/// Reads every entry to find which dates link to which.
+pub fn backlinks(dir: &Path, dates: &BTreeSet<Date>, vault: Option<&Vault>) -> Backlinks {
+    let mut backlinks = Backlinks::new();
+    for date in dates {
+        let Ok(Some(text)) = journal::read_entry(dir, *date, vault) else {
+            continue;
+        };
+        for target in targets(&text, *date) {
+            backlinks.entry(target).or_default().insert(*date);
+        }
+    }
+    backlinks
+}
+
+/// Draws the entries linking to the selected date, numbered from `first` so the keys carry on
+/// from the panel above. Returns how many it listed.
+pub fn draw_backlinks(
+    app: &mut AppState,
+    frame: &mut Frame,
+    area: Rect,
+    first: usize,
+    open_key: &str,
+) -> usize {
+    let sources = app.links_to(app.selected_date);
+    let mut lines = Vec::new();
+    for (i, source) in sources.iter().enumerate() {
+        let number = first + i;
+        let label = if number <= 9 {
+            format!("{} {}", number, source)
+        } else {
+            format!("  {}", source)
+        };
+        lines.push(Line::styled(label, app.theme.month_header));
+        let preview = app.entry_preview(*source).unwrap_or_default();
+        lines.push(Line::from(preview.to_string()));
+        lines.push(Line::from(""));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(format!(
+            "no entry links to this day {}",
+            app.glyphs.shrug
+        )));
+    } else {
+        lines.push(Line::styled(
+            format!("press `{}` to read one", open_key),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .title("linked from")
+                .border_style(app.theme.panel_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.panel_border),
+        ),
+        area,
+    );
+    sources.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::parse_date;
+
+    #[test]
+    fn test_targets_and_link_at() {
+        let date = |text| parse_date(text).unwrap();
+        let today = date("2024-03-13");
+        let text = "met [[2024-01-15|Ana]] again, like [[yesterday]]\n\
+                    ```\n[[2020-01-01]]\n```\n\
+                    [[today]] [[not a date]] [[last monday]]";
+        let found: Vec<Date> = targets(text, today).into_iter().collect();
+        assert_eq!(
+            found,
+            [date("2024-01-15"), date("2024-03-11"), date("2024-03-12")]
+        );
+
+        let lines: Vec<String> = text.lines().map(String::from).collect();
+        assert_eq!(link_at(&lines, 0, 4, today), Some(date("2024-01-15")));
+        assert_eq!(link_at(&lines, 0, 21, today), Some(date("2024-01-15")));
+        assert_eq!(link_at(&lines, 0, 22, today), None);
+        assert_eq!(link_at(&lines, 2, 3, today), None);
+    }
+}
@@ -27,7 +27,85 @@ pub mod editor;
 // application logic loop
 pub mod update;
 
+// entry files and metadata on disk
+pub mod journal;
+
+// mood and habit tracking
+pub mod tracker;
+
+// markdown syntax highlighting for the editor
+pub mod highlight;
+
+// markdown preview rendering
+pub mod markdown;
+
+// user configuration
+pub mod config;
+
+// colour themes
+pub mod theme;
+
+// terminal colour support
+pub mod palette;
+
+// unicode and ascii decorations
+pub mod glyphs;
+
+// passphrase-based encryption of entries at rest
+pub mod vault;
+
+// named days pinned to the calendar
+pub mod bookmarks;
+
+// past entries written on the same day
+pub mod onthisday;
+
+// read-only display of entries
+pub mod viewer;
+
+// lists of the keys each screen takes
+pub mod help;
+
+// links between entries and their backlinks
+pub mod links;
+
+// files attached to entries
+pub mod attachments;
+
+// relative date expressions such as `last friday`
+pub mod dateexpr;
+
+// single-line text input boxes
+pub mod prompt;
+
+// git-backed version history of entries
+pub mod history;
+
+// revision logs of entries, for history without git
+pub mod revlog;
+
+// checksummed journal archives
+pub mod archive;
+
+// exports to other formats
+pub mod export;
+
+// importers for other journaling tools
+pub mod import;
+
+// command-line subcommands
+pub mod cli;
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // run a subcommand instead of the interface if one was given
+    match cli::parse(std::env::args().skip(1)) {
+        Ok(Some(command)) => return cli::run(command),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
     // create application state instance
     let mut app = AppState::new();
     // initialize logging
@@ -57,9 +135,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // process event queue
         match tui.events.next()? {
             // generic
-            Event::Tick => {}
+            Event::Tick => app.check_idle(),
             // keyinput received
             Event::Key(key_event) => {
+                app.touch();
                 key_event.update(&mut app);
                 // check operating mode
                 match app.mode {
@@ -78,16 +157,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         tui.draw(&mut app)?; //🌑
                                              // process key
                     }
+                    app::Mode::LOCKED => {
+                        // draw widgets
+                        tui.draw(&mut app)?; //🌘
+                                             // process key
+                    }
+                    app::Mode::HISTORY => {
+                        // draw widgets
+                        tui.draw(&mut app)?; //🌖
+                                             // process key
+                    }
+                    app::Mode::VIEWER => {
+                        // draw widgets
+                        tui.draw(&mut app)?; //🌔
+                                             // process key
+                    }
                 }
             }
             // mouseinput received
             Event::Mouse(mouse_event) => {
+                app.touch();
                 mouse_event.update(&mut app);
                 match app.mode {
                     // draw widgets based on operating mode
                     app::Mode::CALENDAR => tui.draw(&mut app)?,
                     app::Mode::EDITOR => tui.draw(&mut app)?,
                     app::Mode::SORT => todo!(),
+                    app::Mode::LOCKED => tui.draw(&mut app)?,
+                    app::Mode::HISTORY => tui.draw(&mut app)?,
+                    app::Mode::VIEWER => tui.draw(&mut app)?,
                 }
             }
             Event::Resize(_width, _height) => tui.resize(&mut app)?,
@@ -0,0 +1,334 @@
+use crate::glyphs::Glyphs;
+use crate::highlight::MarkdownStyles;
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::prelude::*;
+
+/// Converts Markdown into styled text for the preview pane.
+///
+/// `width` is only used to size horizontal rules; the caller is expected to wrap long lines.
+pub fn render(
+    markdown: &str,
+    width: u16,
+    styles: &MarkdownStyles,
+    glyphs: &Glyphs,
+) -> Text<'static> {
+    let mut renderer = Renderer {
+        styles,
+        glyphs,
+        width: width as usize,
+        lines: Vec::new(),
+        spans: Vec::new(),
+        style_stack: vec![Style::default()],
+        lists: Vec::new(),
+        quote_depth: 0,
+        in_code_block: false,
+        done_task: false,
+        in_heading: false,
+    };
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    for event in Parser::new_ext(markdown, options) {
+        renderer.event(event);
+    }
+    renderer.flush();
+    while renderer
+        .lines
+        .last()
+        .is_some_and(|line| line.spans.is_empty())
+    {
+        renderer.lines.pop();
+    }
+    Text::from(renderer.lines)
+}
+
+/// Returns true for lines without any visible content.
+fn is_blank(line: &Line) -> bool {
+    line.spans.iter().all(|span| span.content.is_empty())
+}
+
+/// Walks pulldown-cmark events, accumulating ratatui lines.
+struct Renderer<'s> {
+    styles: &'s MarkdownStyles,
+    glyphs: &'s Glyphs,
+    width: usize,
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    style_stack: Vec<Style>,
+    /// Next number of each open list, `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code_block: bool,
+    /// Set while inside a checked task item, whose text is styled as done.
+    done_task: bool,
+    /// Set while inside a heading, whose `『』` brackets follow the display mode.
+    in_heading: bool,
+}
+
+impl Renderer<'_> {
+    fn style(&self) -> Style {
+        self.style_stack.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, style: Style) {
+        self.style_stack.push(self.style().patch(style));
+    }
+
+    fn pop_style(&mut self) {
+        if self.style_stack.len() > 1 {
+            self.style_stack.pop();
+        }
+    }
+
+    /// Starts a new line carrying the block quote gutter, if any.
+    fn start_line(&mut self) {
+        if self.quote_depth > 0 && self.spans.is_empty() {
+            self.spans.push(Span::styled(
+                self.glyphs.quote.repeat(self.quote_depth),
+                self.styles.quote,
+            ));
+        }
+    }
+
+    /// Ends the current line, if it has any content.
+    fn flush(&mut self) {
+        if !self.spans.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.spans)));
+        }
+    }
+
+    /// Ends the current line and leaves an empty one after it.
+    fn blank(&mut self) {
+        self.flush();
+        if self.lines.last().is_some_and(|line| !is_blank(line)) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let style = self.style();
+        if self.in_code_block {
+            for line in text.lines() {
+                self.start_line();
+                self.spans.push(Span::styled(format!("  {}", line), style));
+                self.flush();
+            }
+            return;
+        }
+        let text = if self.in_heading {
+            text.replace('『', self.glyphs.open)
+                .replace('』', self.glyphs.close)
+        } else {
+            text.to_string()
+        };
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.flush();
+            }
+            if !part.is_empty() {
+                self.start_line();
+                self.spans.push(Span::styled(part.to_string(), style));
+            }
+        }
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => {
+                self.start_line();
+                let style = self.style().patch(self.styles.code);
+                self.spans.push(Span::styled(code.to_string(), style));
+            }
+            Event::SoftBreak => self.spans.push(Span::raw(" ")),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.blank();
+                self.lines.push(Line::styled(
+                    self.glyphs.rule.repeat(self.width.max(3)),
+                    self.styles.list_marker,
+                ));
+                self.lines.push(Line::default());
+            }
+            Event::TaskListMarker(done) => {
+                let mark = if done {
+                    self.glyphs.task_done
+                } else {
+                    self.glyphs.task_open
+                };
+                self.spans.push(Span::styled(mark, self.styles.checkbox));
+                if done {
+                    self.done_task = true;
+                    self.push_style(self.styles.checkbox_done);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.blank();
+                let marker = match level {
+                    HeadingLevel::H1 => "",
+                    HeadingLevel::H2 => self.glyphs.heading2,
+                    _ => self.glyphs.heading3,
+                };
+                self.start_line();
+                self.spans.push(Span::styled(marker, self.styles.heading));
+                let mut style = self.styles.heading;
+                if level == HeadingLevel::H1 {
+                    style = style.add_modifier(Modifier::UNDERLINED);
+                }
+                self.push_style(style);
+                self.in_heading = true;
+            }
+            Tag::Paragraph => self.start_line(),
+            Tag::BlockQuote(_) => {
+                self.blank();
+                self.quote_depth += 1;
+                self.push_style(self.styles.quote);
+            }
+            Tag::CodeBlock(kind) => {
+                self.blank();
+                if let CodeBlockKind::Fenced(lang) = kind {
+                    if !lang.is_empty() {
+                        self.lines
+                            .push(Line::styled(format!("  {}", lang), self.styles.list_marker));
+                    }
+                }
+                self.in_code_block = true;
+                self.push_style(self.styles.code);
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.blank();
+                } else {
+                    self.flush();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                self.start_line();
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}{}. ", indent, *number - 1)
+                    }
+                    _ => format!("{}{}", indent, self.glyphs.bullet),
+                };
+                self.spans
+                    .push(Span::styled(marker, self.styles.list_marker));
+            }
+            Tag::Emphasis => self.push_style(self.styles.emphasis),
+            Tag::Strong => self.push_style(self.styles.strong),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT))
+            }
+            Tag::Link { .. } => self.push_style(self.styles.link),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.in_heading = false;
+                self.pop_style();
+                self.blank();
+            }
+            TagEnd::Paragraph => {
+                if self.lists.is_empty() {
+                    self.blank();
+                } else {
+                    self.flush();
+                }
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.pop_style();
+                self.blank();
+            }
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                self.pop_style();
+                self.blank();
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => {
+                if self.done_task {
+                    self.done_task = false;
+                    self.pop_style();
+                }
+                self.flush();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                self.pop_style()
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyphs::{ASCII, UNICODE};
+
+    fn plain(text: &Text) -> Vec<String> {
+        text.lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_blocks() {
+        let markdown = "# Title\n\nsome *text*\n\n- one\n- [x] two\n\n1. first\n2. second\n\n> quoted\n\n```rust\nlet a;\n\nlet b;\n```\n\n---";
+        let text = render(markdown, 5, &MarkdownStyles::default(), &UNICODE);
+        assert_eq!(
+            plain(&text),
+            vec![
+                "Title",
+                "",
+                "some text",
+                "",
+                "• one",
+                "• ☑ two",
+                "",
+                "1. first",
+                "2. second",
+                "",
+                "│ quoted",
+                "",
+                "  rust",
+                "  let a;",
+                "  ",
+                "  let b;",
+                "",
+                "─────",
+            ]
+        );
+
+        // entry headings are written with fixed brackets and shown with the mode's
+        let heading = "# Daily Entry - 『2024-01-01』";
+        let ascii = render(heading, 40, &MarkdownStyles::default(), &ASCII);
+        assert_eq!(plain(&ascii), ["Daily Entry - [ 2024-01-01 ]"]);
+        let unicode = render(heading, 40, &MarkdownStyles::default(), &UNICODE);
+        assert_eq!(plain(&unicode), ["Daily Entry - 『2024-01-01』"]);
+    }
+}
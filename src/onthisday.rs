@@ -0,0 +1,226 @@
+use crate::app::AppState;
+use crate::attachments::draw_attachments;
+use crate::links::draw_backlinks;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::collections::BTreeSet;
+use time::Date;
+
+/// Width of the panel next to the calendar or editor.
+pub const PANEL_WIDTH: u16 = 36;
+
+/// Most past entries the panel lists, each opened with its number key.
+pub const MOST: usize = 9;
+
+/// How a past entry lines up with the date being looked at.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Echo {
+    /// The same month and day.
+    SameDay,
+    /// The same weekday of the same ISO week, e.g. the Monday of week 11.
+    SameWeekday,
+}
+
+/// A past entry that lines up with the date being looked at.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Memory {
+    pub date: Date,
+    /// Whole years between the two dates.
+    pub years: i32,
+    pub echo: Echo,
+}
+
+/// Returns the entries written on the same day, or the same weekday of the same week, in the
+/// years before `date`, newest first and at most `MOST` of them.
+pub fn memories(date: Date, dates: &BTreeSet<Date>) -> Vec<Memory> {
+    let Some(first) = dates.first() else {
+        return Vec::new();
+    };
+    // weeks belong to ISO years, which can start in the calendar year before or end in the one
+    // after, so the same weekday is looked for in the ISO years before the date's own
+    let (iso_year, week, weekday) = date.to_iso_week_date();
+    let mut memories = Vec::new();
+    for years in 1..=date.year().max(iso_year) - first.year() + 1 {
+        let same_day = date
+            .replace_year(date.year() - years)
+            .ok()
+            .filter(|past| *past < date);
+        if let Some(past) = same_day.filter(|past| dates.contains(past)) {
+            memories.push(Memory {
+                date: past,
+                years,
+                echo: Echo::SameDay,
+            });
+        }
+        let same_weekday = Date::from_iso_week_date(iso_year - years, week, weekday)
+            .ok()
+            .filter(|past| *past < date);
+        if let Some(past) =
+            same_weekday.filter(|past| dates.contains(past) && same_day != Some(*past))
+        {
+            memories.push(Memory {
+                date: past,
+                years,
+                echo: Echo::SameWeekday,
+            });
+        }
+    }
+    memories.truncate(MOST);
+    memories
+}
+
+/// Describes when a memory was written, e.g. `1 year ago` or `Monday of week 11, 2 years ago`.
+fn describe(memory: &Memory) -> String {
+    let ago = match memory.years {
+        1 => "1 year ago".to_string(),
+        years => format!("{} years ago", years),
+    };
+    match memory.echo {
+        Echo::SameDay => ago,
+        Echo::SameWeekday => format!(
+            "{} of week {}, {}",
+            memory.date.weekday(),
+            memory.date.iso_week(),
+            ago
+        ),
+    }
+}
+
+/// Draws the panels shown next to the calendar, editor or viewer, one above the other. Past
+/// entries and backlinks are numbered as one list, opened with `open_key`; attachments are
+/// listed whenever the date has some, picked after pressing `files_key`.
+pub fn draw_side_panels(
+    app: &mut AppState,
+    frame: &mut Frame,
+    area: Rect,
+    open_key: &str,
+    files_key: &str,
+) {
+    let files = !app.attachment_names(app.selected_date).is_empty();
+    let shown = [app.on_this_day, app.backlinks, files];
+    let count = shown.iter().filter(|shown| **shown).count() as u32;
+    let areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, count.max(1)); count as usize])
+        .split(area);
+    let mut listed = 0;
+    if app.on_this_day {
+        listed = draw_on_this_day(app, frame, areas[0], open_key);
+    }
+    if app.backlinks {
+        let area = areas[app.on_this_day as usize];
+        draw_backlinks(app, frame, area, listed + 1, open_key);
+    }
+    if files {
+        draw_attachments(app, frame, areas[areas.len() - 1], files_key);
+    }
+}
+
+/// Draws the past entries of the selected date, each with the key that opens it. Returns how
+/// many it listed.
+pub fn draw_on_this_day(
+    app: &mut AppState,
+    frame: &mut Frame,
+    area: Rect,
+    open_key: &str,
+) -> usize {
+    let dates = app.journal_index().dates.clone();
+    let memories = memories(app.selected_date, &dates);
+    let mut lines = Vec::new();
+    for (i, memory) in memories.iter().enumerate() {
+        lines.push(Line::styled(
+            format!("{} {} ({})", i + 1, memory.date, describe(memory)),
+            app.theme.month_header,
+        ));
+        let preview = app.entry_preview(memory.date).unwrap_or_default();
+        lines.push(Line::from(preview.to_string()));
+        lines.push(Line::from(""));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(format!(
+            "nothing written on this day in earlier years {}",
+            app.glyphs.shrug
+        )));
+    } else {
+        lines.push(Line::styled(
+            format!("press `{}` to read one", open_key),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .title("on this day")
+                .border_style(app.theme.panel_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.panel_border),
+        ),
+        area,
+    );
+    memories.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::parse_date;
+
+    #[test]
+    fn test_memories() {
+        let date = |text| parse_date(text).unwrap();
+        let dates: BTreeSet<Date> = [
+            "2021-03-13",
+            "2022-03-13",
+            // the Wednesday of week 11 in 2023, and again in 2020
+            "2023-03-15",
+            "2020-03-11",
+            "2023-03-14",
+            "2024-03-13",
+        ]
+        .into_iter()
+        .map(date)
+        .collect();
+
+        let found = memories(date("2024-03-13"), &dates);
+        let summary: Vec<(Date, i32, Echo)> = found
+            .iter()
+            .map(|memory| (memory.date, memory.years, memory.echo))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (date("2023-03-15"), 1, Echo::SameWeekday),
+                (date("2022-03-13"), 2, Echo::SameDay),
+                (date("2021-03-13"), 3, Echo::SameDay),
+                (date("2020-03-11"), 4, Echo::SameWeekday),
+            ]
+        );
+        assert_eq!(describe(&found[0]), "Wednesday of week 11, 1 year ago");
+        assert_eq!(describe(&found[1]), "2 years ago");
+
+        // the 29th of February only lines up with leap years
+        let leap: BTreeSet<Date> = [date("2020-02-29")].into_iter().collect();
+        assert_eq!(memories(date("2024-02-29"), &leap).len(), 1);
+        assert!(memories(date("2023-03-01"), &leap).is_empty());
+
+        // 2021-01-01 is the Friday of week 53 of 2020, and 2016-01-01 that of week 53 of 2015
+        let new_year: BTreeSet<Date> = ["2016-01-01", "2020-01-01", "2021-01-01"]
+            .into_iter()
+            .map(date)
+            .collect();
+        let found: Vec<(Date, i32, Echo)> = memories(date("2021-01-01"), &new_year)
+            .iter()
+            .map(|memory| (memory.date, memory.years, memory.echo))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (date("2020-01-01"), 1, Echo::SameDay),
+                (date("2016-01-01"), 5, Echo::SameDay),
+            ]
+        );
+    }
+}
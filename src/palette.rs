@@ -0,0 +1,248 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+/// How many colours the terminal can display.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ColorSupport {
+    /// No colour at all, only modifiers such as bold and reverse.
+    #[serde(rename = "none")]
+    NoColor,
+    /// The 16 standard ANSI colours.
+    #[serde(rename = "16")]
+    Ansi16,
+    /// The xterm 256-colour palette.
+    #[serde(rename = "256")]
+    Ansi256,
+    /// 24-bit RGB.
+    #[serde(rename = "truecolor")]
+    TrueColor,
+}
+
+impl std::fmt::Display for ColorSupport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ColorSupport::NoColor => write!(f, "no colour"),
+            ColorSupport::Ansi16 => write!(f, "16 colours"),
+            ColorSupport::Ansi256 => write!(f, "256 colours"),
+            ColorSupport::TrueColor => write!(f, "truecolor"),
+        }
+    }
+}
+
+/// Detects colour support from the process environment.
+pub fn detect() -> ColorSupport {
+    detect_from(|name| std::env::var(name).ok())
+}
+
+/// Detects colour support from environment variables looked up through `var`.
+///
+/// `NO_COLOR` (any non-empty value) wins, then `COLORTERM`, then well-known terminals and
+/// finally `TERM`.
+pub fn detect_from(var: impl Fn(&str) -> Option<String>) -> ColorSupport {
+    if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return ColorSupport::NoColor;
+    }
+    let colorterm = var("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorSupport::TrueColor;
+    }
+    if var("WT_SESSION").is_some() {
+        return ColorSupport::TrueColor;
+    }
+    let term = var("TERM").unwrap_or_default().to_ascii_lowercase();
+    if term.is_empty() {
+        // windows consoles do not set TERM but support colour
+        return if cfg!(windows) {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        };
+    }
+    if term == "dumb" {
+        ColorSupport::NoColor
+    } else if term.contains("truecolor") || term.contains("direct") {
+        ColorSupport::TrueColor
+    } else if term.contains("256") {
+        ColorSupport::Ansi256
+    } else {
+        ColorSupport::Ansi16
+    }
+}
+
+/// The 16 ANSI colours with the RGB values xterm uses for them.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel levels of the xterm 6x6x6 colour cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// Returns the RGB value of an indexed colour in the xterm palette.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Finds the closest colour of the 256-colour cube or grey ramp.
+///
+/// The first 16 entries are skipped because terminals let users redefine them.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - channel as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let grey = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+    if distance(indexed_rgb(grey), rgb) < distance(indexed_rgb(cube), rgb) {
+        grey
+    } else {
+        cube
+    }
+}
+
+/// Finds the closest of the 16 ANSI colours.
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, ansi)| distance(*ansi, rgb))
+        .map(|(colour, _)| *colour)
+        .unwrap_or(Color::Reset)
+}
+
+/// Maps a colour to the nearest one the terminal can show.
+pub fn degrade_color(colour: Color, support: ColorSupport) -> Color {
+    let rgb = match colour {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(i) => Some(indexed_rgb(i)),
+        _ => None,
+    };
+    match (support, rgb) {
+        (ColorSupport::TrueColor, _) => colour,
+        (ColorSupport::NoColor, _) => Color::Reset,
+        (_, None) => colour,
+        (ColorSupport::Ansi256, Some(_)) if matches!(colour, Color::Indexed(_)) => colour,
+        (ColorSupport::Ansi256, Some(rgb)) => Color::Indexed(nearest_256(rgb)),
+        (ColorSupport::Ansi16, Some(rgb)) => nearest_16(rgb),
+    }
+}
+
+/// Maps a style's colours to the nearest ones the terminal can show, keeping its modifiers.
+pub fn degrade_style(style: Style, support: ColorSupport) -> Style {
+    let mut degraded = style;
+    if support == ColorSupport::NoColor {
+        degraded.fg = None;
+        degraded.bg = None;
+        return degraded;
+    }
+    degraded.fg = style.fg.map(|colour| degrade_color(colour, support));
+    degraded.bg = style.bg.map(|colour| degrade_color(colour, support));
+    degraded
+}
+
+/// Marks a style that relied on a background colour so it stays visible without colour.
+pub fn emphasise_without_color(style: Style, support: ColorSupport) -> Style {
+    if support == ColorSupport::NoColor {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_environment() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert_eq!(
+            detect_from(env(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")])),
+            ColorSupport::NoColor
+        );
+        assert_eq!(
+            detect_from(env(&[("NO_COLOR", ""), ("COLORTERM", "24bit")])),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect_from(env(&[("TERM", "xterm-256color")])),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(detect_from(env(&[("TERM", "linux")])), ColorSupport::Ansi16);
+        assert_eq!(detect_from(env(&[("TERM", "dumb")])), ColorSupport::NoColor);
+    }
+
+    #[test]
+    fn test_degrade_color() {
+        let amber = Color::Rgb(255, 225, 120);
+        assert_eq!(degrade_color(amber, ColorSupport::TrueColor), amber);
+        assert_eq!(
+            degrade_color(amber, ColorSupport::Ansi256),
+            Color::Indexed(222)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(128, 128, 128), ColorSupport::Ansi256),
+            Color::Indexed(244)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(70, 100, 255), ColorSupport::Ansi16),
+            Color::LightBlue
+        );
+        assert_eq!(
+            degrade_color(Color::Indexed(196), ColorSupport::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            degrade_color(Color::Green, ColorSupport::NoColor),
+            Color::Reset
+        );
+
+        let style = Style::default()
+            .fg(amber)
+            .bg(Color::Black)
+            .add_modifier(Modifier::BOLD);
+        let plain = degrade_style(style, ColorSupport::NoColor);
+        assert_eq!((plain.fg, plain.bg), (None, None));
+        assert!(plain.add_modifier.contains(Modifier::BOLD));
+    }
+}
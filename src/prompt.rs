@@ -0,0 +1,106 @@
+use crate::glyphs::Glyphs;
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// A single line of text input shown in a small box over the rest of the interface.
+#[derive(Default, Clone, Debug)]
+pub struct Prompt {
+    /// Text shown above the input.
+    pub label: String,
+    /// What has been typed so far.
+    pub input: String,
+    /// Message shown under the input after a rejected submission.
+    pub error: Option<String>,
+    /// Whether the input is hidden, as for a passphrase.
+    pub masked: bool,
+}
+
+/// What a key press did to a prompt.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PromptEvent {
+    /// Still typing.
+    Pending,
+    /// Enter was pressed; holds the input.
+    Submit(String),
+    /// Esc was pressed.
+    Cancel,
+}
+
+impl Prompt {
+    /// Creates an empty prompt.
+    pub fn new(label: &str, masked: bool) -> Self {
+        Prompt {
+            label: label.to_string(),
+            masked,
+            ..Default::default()
+        }
+    }
+
+    /// Applies a key press, taking the input out of the prompt when it is submitted.
+    pub fn input(&mut self, key: &KeyEvent) -> PromptEvent {
+        match key.code {
+            KeyCode::Enter => return PromptEvent::Submit(std::mem::take(&mut self.input)),
+            KeyCode::Esc => return PromptEvent::Cancel,
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.clear()
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c)
+            }
+            _ => {}
+        }
+        PromptEvent::Pending
+    }
+
+    /// Shows why the last submission was rejected.
+    pub fn fail(&mut self, error: impl ToString) {
+        self.error = Some(error.to_string());
+    }
+}
+
+/// Returns a rectangle of the given size centred in `area`, clamped to fit.
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Draws the prompt in a box centred in `area`.
+pub fn draw_prompt(prompt: &Prompt, theme: &Theme, glyphs: &Glyphs, frame: &mut Frame, area: Rect) {
+    let text = if prompt.masked {
+        "*".repeat(prompt.input.chars().count())
+    } else {
+        prompt.input.clone()
+    };
+    let mut lines = vec![Line::from(format!("> {}", text))];
+    if let Some(error) = &prompt.error {
+        lines.push(Line::styled(
+            error.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+    }
+    let area = centered(area, 50, 2 + lines.len() as u16);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(prompt.label.as_str())
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        area,
+    );
+}
@@ -0,0 +1,207 @@
+use crate::journal;
+use crate::vault::{self, Vault};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use std::fs;
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use time::{Date, OffsetDateTime, UtcOffset};
+
+/// Directory, inside the entries directory, that holds the revision logs.
+pub const REVISIONS_DIR: &str = ".revisions";
+
+/// Prefix of every revision log.
+const MAGIC: &[u8] = b"DDREVLOG1\n";
+
+/// One saved state of an entry, as stored in its revision log.
+///
+/// On disk a record is the save time (`i64`), the payload length (`u32`), both big-endian, and
+/// the payload: the deflated text, sealed when the journal is encrypted.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Record {
+    /// Unix time of the save.
+    pub time: i64,
+    pub payload: Vec<u8>,
+}
+
+/// Returns the path of the revision log for a date.
+pub fn revlog_path(dir: &Path, date: Date) -> PathBuf {
+    dir.join(REVISIONS_DIR).join(format!("{}.log", date))
+}
+
+/// Reads every record of a revision log, oldest first. A missing log has no records.
+pub fn read_records(path: &Path) -> io::Result<Vec<Record>> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let damaged = || io::Error::new(ErrorKind::InvalidData, "damaged revision log");
+    let mut rest = data.strip_prefix(MAGIC).ok_or_else(damaged)?;
+    let mut records = Vec::new();
+    while !rest.is_empty() {
+        if rest.len() < 12 {
+            return Err(damaged());
+        }
+        let (header, tail) = rest.split_at(12);
+        let time = i64::from_be_bytes(header[..8].try_into().map_err(|_| damaged())?);
+        let len = u32::from_be_bytes(header[8..].try_into().map_err(|_| damaged())?) as usize;
+        if tail.len() < len {
+            return Err(damaged());
+        }
+        let (payload, tail) = tail.split_at(len);
+        records.push(Record {
+            time,
+            payload: payload.to_vec(),
+        });
+        rest = tail;
+    }
+    Ok(records)
+}
+
+/// Replaces a revision log with the given records.
+pub fn write_records(path: &Path, records: &[Record]) -> io::Result<()> {
+    let mut data = MAGIC.to_vec();
+    for record in records {
+        let len = u32::try_from(record.payload.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "revision too large"))?;
+        data.extend_from_slice(&record.time.to_be_bytes());
+        data.extend_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&record.payload);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    journal::write_atomic(path, &data)
+}
+
+/// Compresses and, for an encrypted journal, seals the text of a revision in the log at `path`.
+fn encode(text: &str, path: &Path, vault: Option<&Vault>) -> io::Result<Vec<u8>> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(text.as_bytes())?;
+    vault::encode(vault, &vault::sealed_name(path), &encoder.finish()?)
+}
+
+/// Recovers the text of a revision in the log at `path`.
+fn decode(payload: &[u8], path: &Path, vault: Option<&Vault>) -> io::Result<String> {
+    let deflated = vault::decode(vault, &vault::sealed_name(path), payload.to_vec())?;
+    let mut text = String::new();
+    DeflateDecoder::new(deflated.as_slice()).read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Appends a revision unless the text is the same as the latest one. Returns whether it did.
+pub fn append(
+    dir: &Path,
+    date: Date,
+    text: &str,
+    time: i64,
+    vault: Option<&Vault>,
+) -> io::Result<bool> {
+    let path = revlog_path(dir, date);
+    let mut records = read_records(&path)?;
+    if let Some(latest) = records.last() {
+        if decode(&latest.payload, &path, vault)? == text {
+            return Ok(false);
+        }
+    }
+    records.push(Record {
+        time,
+        payload: encode(text, &path, vault)?,
+    });
+    write_records(&path, &records)?;
+    Ok(true)
+}
+
+/// Lists the save times of the revisions of a date, newest first, with their record numbers.
+pub fn list(dir: &Path, date: Date) -> io::Result<Vec<(usize, i64)>> {
+    let records = read_records(&revlog_path(dir, date))?;
+    Ok(records
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(number, record)| (number, record.time))
+        .collect())
+}
+
+/// Reads the text of a revision by its record number.
+pub fn read(dir: &Path, date: Date, number: usize, vault: Option<&Vault>) -> io::Result<String> {
+    let path = revlog_path(dir, date);
+    let records = read_records(&path)?;
+    let record = records
+        .get(number)
+        .ok_or_else(|| io::Error::new(ErrorKind::NotFound, "no such revision"))?;
+    decode(&record.payload, &path, vault)
+}
+
+/// Re-encodes every revision of a log, e.g. to seal or open it when a journal is encrypted or
+/// decrypted.
+pub fn reencode(path: &Path, from: Option<&Vault>, to: Option<&Vault>) -> io::Result<()> {
+    let name = vault::sealed_name(path);
+    let records = read_records(path)?
+        .into_iter()
+        .map(|record| {
+            let deflated = vault::decode_migrating(from, &name, record.payload)?;
+            Ok(Record {
+                time: record.time,
+                payload: vault::encode(to, &name, &deflated)?,
+            })
+        })
+        .collect::<io::Result<Vec<Record>>>()?;
+    write_records(path, &records)
+}
+
+/// Lists every revision log in the entries directory.
+pub fn revlog_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    match fs::read_dir(dir.join(REVISIONS_DIR)) {
+        Ok(read_dir) => {
+            for entry in read_dir {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "log") {
+                    files.push(path);
+                }
+            }
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Formats a unix time as local `YYYY-MM-DD HH:MM:SS`.
+pub fn format_time(time: i64) -> String {
+    let utc = OffsetDateTime::from_unix_timestamp(time).unwrap_or(OffsetDateTime::UNIX_EPOCH);
+    let local = utc.to_offset(UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        local.date(),
+        local.hour(),
+        local.minute(),
+        local.second()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_read_revisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::create(dir.path(), "pass").unwrap();
+        let date = Date::from_calendar_date(2024, time::Month::May, 9).unwrap();
+        let vault = Some(&vault);
+
+        assert!(append(dir.path(), date, "one", 10, vault).unwrap());
+        assert!(!append(dir.path(), date, "one", 11, vault).unwrap());
+        assert!(append(dir.path(), date, "two", 12, vault).unwrap());
+        assert_eq!(list(dir.path(), date).unwrap(), [(1, 12), (0, 10)]);
+        assert_eq!(read(dir.path(), date, 0, vault).unwrap(), "one");
+        assert!(read(dir.path(), date, 0, None).is_err());
+
+        let path = revlog_path(dir.path(), date);
+        reencode(&path, vault, None).unwrap();
+        assert_eq!(read(dir.path(), date, 1, None).unwrap(), "two");
+    }
+}
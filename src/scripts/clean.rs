@@ -1,30 +1,254 @@
 use std::fs::{self, read_dir, remove_file};
 use std::io::{self, ErrorKind};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-fn main() -> io::Result<()> {
-    // Define the path to the 'entries' directory and the 'app.log' file.
-    let entries_dir = Path::new("entries");
-    let log_file = Path::new("app.log");
+const USAGE: &str = "usage: clean [--yes] [--before YYYY-MM-DD] [--after YYYY-MM-DD] [--logs-only]
+       clean restore [--yes]
+
+Lists what would be removed; nothing changes without --yes.
+Entries are moved to entries/.trash and can be brought back with `clean restore`.
+--before and --after only select entries dated strictly before or after the given day.";
+
+/// Command-line options.
+#[derive(Default, Debug, PartialEq)]
+struct Options {
+    yes: bool,
+    restore: bool,
+    logs_only: bool,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+    if args.peek().map(String::as_str) == Some("restore") {
+        args.next();
+        options.restore = true;
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--yes" | "-y" => options.yes = true,
+            "--logs-only" => options.logs_only = true,
+            "--before" | "--after" => {
+                let date = args.next().ok_or(format!("{} needs a date", arg))?;
+                if !is_date(&date) {
+                    return Err(format!("`{}` is not a YYYY-MM-DD date", date));
+                }
+                if arg == "--before" {
+                    options.before = Some(date);
+                } else {
+                    options.after = Some(date);
+                }
+            }
+            _ => return Err(format!("unknown argument `{}`", arg)),
+        }
+    }
+    if options.restore && (options.logs_only || options.before.is_some() || options.after.is_some())
+    {
+        return Err("restore only takes --yes".to_string());
+    }
+    Ok(options)
+}
+
+/// Returns true for a `YYYY-MM-DD` date. Such dates sort correctly as strings.
+fn is_date(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
 
-    // Empty the 'entries' directory.
-    if entries_dir.is_dir() {
-        for entry in read_dir(entries_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+/// Returns the date an entry file name starts with, e.g. `2024-01-15_entry.md`.
+fn file_date(name: &str) -> Option<&str> {
+    name.get(..10).filter(|prefix| is_date(prefix))
+}
+
+/// Decides whether an entries file is selected by the date filters.
+///
+/// Hidden files, such as the key file of an encrypted journal, are never selected, and files
+/// without a date are only selected when no filter is given.
+fn is_selected(name: &str, options: &Options) -> bool {
+    if name.starts_with('.') {
+        return false;
+    }
+    match file_date(name) {
+        Some(date) => {
+            options.before.as_deref().is_none_or(|before| date < before)
+                && options.after.as_deref().is_none_or(|after| date > after)
+        }
+        None => options.before.is_none() && options.after.is_none(),
+    }
+}
+
+/// Lists the plain files directly inside `dir`, sorted by name.
+fn files_in(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if dir.is_dir() {
+        for entry in read_dir(dir)? {
+            let path = entry?.path();
             if path.is_file() {
-                fs::remove_file(path)?;
+                files.push(path);
             }
         }
     }
+    files.sort();
+    Ok(files)
+}
 
-    // Delete the 'app.log' file.
-    match remove_file(log_file) {
-        Ok(_) => println!("Removed 'app.log'."),
-        Err(e) if e.kind() == ErrorKind::NotFound => println!("'app.log' not found."),
-        Err(e) => return Err(e),
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Moves selected entries to a new trash folder and removes the log.
+fn clean(entries_dir: &Path, log_file: &Path, options: &Options) -> io::Result<()> {
+    let entries: Vec<PathBuf> = if options.logs_only {
+        Vec::new()
+    } else {
+        files_in(entries_dir)?
+            .into_iter()
+            .filter(|path| is_selected(&file_name(path), options))
+            .collect()
+    };
+    let clean_log = options.logs_only || (options.before.is_none() && options.after.is_none());
+    let clean_log = clean_log && log_file.is_file();
+
+    if entries.is_empty() && !clean_log {
+        println!("Nothing to clean.");
+        return Ok(());
+    }
+    let verb = if options.yes {
+        "Removing"
+    } else {
+        "Would remove"
+    };
+    for path in &entries {
+        println!("{} {} (to trash)", verb, path.display());
+    }
+    if clean_log {
+        println!("{} {}", verb, log_file.display());
+    }
+    if !options.yes {
+        println!("Run again with --yes to do this.");
+        return Ok(());
     }
 
-    println!("'entries' directory has been emptied.");
+    if !entries.is_empty() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let trash = entries_dir.join(".trash").join(stamp.to_string());
+        fs::create_dir_all(&trash)?;
+        for path in &entries {
+            fs::rename(path, trash.join(file_name(path)))?;
+        }
+        println!(
+            "Moved {} files to '{}'. Undo with `clean restore --yes`.",
+            entries.len(),
+            trash.display()
+        );
+    }
+    if clean_log {
+        match remove_file(log_file) {
+            Ok(_) => println!("Removed '{}'.", log_file.display()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Moves everything in the trash back into the entries directory, keeping files that exist.
+fn restore(entries_dir: &Path, options: &Options) -> io::Result<()> {
+    let trash = entries_dir.join(".trash");
+    let mut batches = Vec::new();
+    if trash.is_dir() {
+        for entry in read_dir(&trash)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                batches.push(path);
+            }
+        }
+    }
+    batches.sort();
+
+    let mut restored = 0;
+    for batch in &batches {
+        for path in files_in(batch)? {
+            let target = entries_dir.join(file_name(&path));
+            if target.exists() {
+                println!("Keeping {} (already exists)", target.display());
+                continue;
+            }
+            if options.yes {
+                fs::rename(&path, &target)?;
+            } else {
+                println!("Would restore {}", target.display());
+            }
+            restored += 1;
+        }
+        if options.yes {
+            // only succeeds once nothing is left in the batch
+            let _ = fs::remove_dir(batch);
+        }
+    }
+    if !options.yes {
+        if restored > 0 {
+            println!("Run again with --yes to do this.");
+        } else {
+            println!("Nothing to restore.");
+        }
+    } else {
+        println!("Restored {} files.", restored);
+    }
     Ok(())
 }
+
+fn main() -> io::Result<()> {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let entries_dir = Path::new("entries");
+    let log_file = Path::new("app.log");
+    if options.restore {
+        restore(entries_dir, &options)
+    } else {
+        clean(entries_dir, log_file, &options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Result<Options, String> {
+        parse_args(text.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_selection() {
+        let options = args("--before 2024-03-01 --after 2024-01-31").unwrap();
+        assert!(!options.yes);
+        assert!(is_selected("2024-02-10_entry.md", &options));
+        assert!(is_selected("2024-02-10_meta.toml", &options));
+        assert!(!is_selected("2024-03-01_entry.md", &options));
+        assert!(!is_selected("2024-01-31_entry.md", &options));
+        assert!(!is_selected("notes.txt", &options));
+        assert!(!is_selected(".daydream-vault", &Options::default()));
+        assert!(is_selected("notes.txt", &Options::default()));
+
+        assert!(args("restore --yes").unwrap().restore);
+        assert!(args("restore --logs-only").is_err());
+        assert!(args("--before 2024-3-1").is_err());
+    }
+}
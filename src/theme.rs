@@ -0,0 +1,551 @@
+use crate::highlight::MarkdownStyles;
+use crate::palette::{degrade_color, degrade_style, emphasise_without_color, ColorSupport};
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Every colour and style used by the interface.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub name: String,
+    /// Border of the title bars above the calendar and editor.
+    pub title_border: Style,
+    /// Border of the editor on a regular day.
+    pub editor_border: Style,
+    /// Border of the editor on a holiday.
+    pub holiday_border: Style,
+    /// Border of side panels such as the tracker form and preview.
+    pub panel_border: Style,
+    /// Today's date on the calendar.
+    pub today: Style,
+    pub holiday: Style,
+    pub season: Style,
+    /// Bookmarked days on the calendar.
+    pub bookmark: Style,
+    /// The date under the cursor on the calendar.
+    pub selected: Style,
+    pub month_header: Style,
+    pub weekday_header: Style,
+    /// Any day of the month without a more specific style.
+    pub day: Style,
+    /// Days of the neighbouring months.
+    pub surrounding: Style,
+    /// Days with an entry, in the entries overlay.
+    pub entry: Style,
+    /// Days where the habit was done, in a habit overlay.
+    pub habit: Style,
+    /// Lines added since a past version, in the history diff.
+    pub added: Style,
+    /// Lines removed since a past version, in the history diff.
+    pub removed: Style,
+    /// Background of mood scores 1 to 5, in the mood overlay.
+    pub mood: [Color; 5],
+    pub markdown: MarkdownStyles,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        dark()
+    }
+}
+
+/// The original daydream colours.
+pub fn dark() -> Theme {
+    Theme {
+        name: "dark".to_string(),
+        title_border: Style::default().fg(Color::LightGreen),
+        editor_border: Style::default().fg(Color::Rgb(255, 225, 120)),
+        holiday_border: Style::default().fg(Color::Rgb(255, 255, 160)),
+        panel_border: Style::default(),
+        today: Style::default()
+            .add_modifier(Modifier::SLOW_BLINK)
+            .fg(Color::Rgb(55, 55, 255))
+            .bg(Color::Rgb(255, 255, 160)),
+        holiday: Style::default()
+            .add_modifier(Modifier::UNDERLINED)
+            .fg(Color::Yellow)
+            .bg(Color::Rgb(70, 100, 255)),
+        season: Style::default()
+            .fg(Color::Red)
+            .bg(Color::LightYellow)
+            .add_modifier(Modifier::BOLD),
+        bookmark: Style::default()
+            .fg(Color::Rgb(255, 160, 220))
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        selected: Style::default()
+            .add_modifier(Modifier::CROSSED_OUT)
+            .bg(Color::White),
+        month_header: Style::default(),
+        weekday_header: Style::default().fg(Color::Green),
+        day: Style::default().fg(Color::White).bg(Color::DarkGray),
+        surrounding: Style::default().add_modifier(Modifier::DIM),
+        entry: Style::default()
+            .fg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD),
+        habit: Style::default().fg(Color::Black).bg(Color::LightGreen),
+        added: Style::default().fg(Color::LightGreen),
+        removed: Style::default().fg(Color::LightRed),
+        mood: [
+            Color::Rgb(220, 60, 60),
+            Color::Rgb(235, 140, 60),
+            Color::Rgb(230, 210, 80),
+            Color::Rgb(150, 210, 90),
+            Color::Rgb(60, 190, 90),
+        ],
+        markdown: MarkdownStyles::default(),
+    }
+}
+
+/// Dark text on a light background.
+pub fn light() -> Theme {
+    Theme {
+        name: "light".to_string(),
+        title_border: Style::default().fg(Color::Green),
+        editor_border: Style::default().fg(Color::Rgb(180, 120, 0)),
+        holiday_border: Style::default().fg(Color::Rgb(200, 80, 0)),
+        panel_border: Style::default().fg(Color::DarkGray),
+        today: Style::default()
+            .fg(Color::White)
+            .bg(Color::Blue)
+            .add_modifier(Modifier::BOLD),
+        holiday: Style::default()
+            .fg(Color::Rgb(20, 40, 160))
+            .bg(Color::Rgb(200, 215, 255))
+            .add_modifier(Modifier::UNDERLINED),
+        season: Style::default()
+            .fg(Color::Rgb(160, 30, 30))
+            .bg(Color::Rgb(255, 240, 190))
+            .add_modifier(Modifier::BOLD),
+        bookmark: Style::default()
+            .fg(Color::Rgb(170, 0, 110))
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        selected: Style::default()
+            .fg(Color::White)
+            .bg(Color::Rgb(200, 80, 0))
+            .add_modifier(Modifier::BOLD),
+        month_header: Style::default()
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+        weekday_header: Style::default().fg(Color::Rgb(0, 120, 60)),
+        day: Style::default()
+            .fg(Color::Black)
+            .bg(Color::Rgb(245, 245, 240)),
+        surrounding: Style::default().fg(Color::Gray),
+        entry: Style::default()
+            .fg(Color::Rgb(0, 100, 160))
+            .add_modifier(Modifier::BOLD),
+        habit: Style::default()
+            .fg(Color::Black)
+            .bg(Color::Rgb(160, 230, 160)),
+        added: Style::default().fg(Color::Rgb(0, 120, 40)),
+        removed: Style::default().fg(Color::Rgb(180, 30, 30)),
+        mood: [
+            Color::Rgb(240, 150, 150),
+            Color::Rgb(250, 195, 140),
+            Color::Rgb(250, 235, 150),
+            Color::Rgb(200, 235, 160),
+            Color::Rgb(150, 220, 170),
+        ],
+        markdown: MarkdownStyles {
+            heading: Style::default()
+                .fg(Color::Rgb(0, 70, 160))
+                .add_modifier(Modifier::BOLD),
+            list_marker: Style::default().fg(Color::Rgb(0, 130, 60)),
+            code: Style::default()
+                .fg(Color::Rgb(160, 30, 30))
+                .bg(Color::Rgb(235, 235, 235)),
+            link: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED),
+            checkbox: Style::default().fg(Color::Rgb(180, 120, 0)),
+            checkbox_done: Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::CROSSED_OUT),
+            tag: Style::default().fg(Color::Magenta),
+            quote: Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+            ..MarkdownStyles::default()
+        },
+    }
+}
+
+/// Only black, white and yellow, for readability over colour.
+pub fn high_contrast() -> Theme {
+    let bright = Style::default()
+        .fg(Color::White)
+        .add_modifier(Modifier::BOLD);
+    let accent = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let inverse = Style::default()
+        .fg(Color::Black)
+        .bg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    Theme {
+        name: "high-contrast".to_string(),
+        title_border: bright,
+        editor_border: accent,
+        holiday_border: accent,
+        panel_border: bright,
+        today: Style::default()
+            .fg(Color::Black)
+            .bg(Color::White)
+            .add_modifier(Modifier::BOLD),
+        holiday: accent.add_modifier(Modifier::UNDERLINED),
+        season: accent.add_modifier(Modifier::ITALIC),
+        bookmark: bright.add_modifier(Modifier::UNDERLINED),
+        selected: inverse,
+        month_header: bright,
+        weekday_header: accent,
+        day: Style::default().fg(Color::White).bg(Color::Black),
+        surrounding: Style::default().fg(Color::Gray),
+        entry: bright.add_modifier(Modifier::UNDERLINED),
+        habit: inverse,
+        added: Style::default()
+            .fg(Color::LightGreen)
+            .add_modifier(Modifier::BOLD),
+        removed: Style::default()
+            .fg(Color::LightRed)
+            .add_modifier(Modifier::CROSSED_OUT),
+        mood: [
+            Color::Red,
+            Color::LightRed,
+            Color::Yellow,
+            Color::LightGreen,
+            Color::Green,
+        ],
+        markdown: MarkdownStyles {
+            heading: accent.add_modifier(Modifier::UNDERLINED),
+            list_marker: accent,
+            code: Style::default().fg(Color::Black).bg(Color::White),
+            link: bright.add_modifier(Modifier::UNDERLINED),
+            checkbox: accent,
+            checkbox_done: Style::default()
+                .fg(Color::Gray)
+                .add_modifier(Modifier::CROSSED_OUT),
+            tag: accent,
+            quote: bright.add_modifier(Modifier::ITALIC),
+            ..MarkdownStyles::default()
+        },
+    }
+}
+
+/// Ethan Schoonover's Solarized palette on its dark background.
+pub fn solarized() -> Theme {
+    let base03 = Color::Rgb(0, 43, 54);
+    let base02 = Color::Rgb(7, 54, 66);
+    let base01 = Color::Rgb(88, 110, 117);
+    let base0 = Color::Rgb(131, 148, 150);
+    let base1 = Color::Rgb(147, 161, 161);
+    let yellow = Color::Rgb(181, 137, 0);
+    let orange = Color::Rgb(203, 75, 22);
+    let red = Color::Rgb(220, 50, 47);
+    let magenta = Color::Rgb(211, 54, 130);
+    let violet = Color::Rgb(108, 113, 196);
+    let blue = Color::Rgb(38, 139, 210);
+    let cyan = Color::Rgb(42, 161, 152);
+    let green = Color::Rgb(133, 153, 0);
+    Theme {
+        name: "solarized".to_string(),
+        title_border: Style::default().fg(green),
+        editor_border: Style::default().fg(yellow),
+        holiday_border: Style::default().fg(orange),
+        panel_border: Style::default().fg(base01),
+        today: Style::default()
+            .fg(base03)
+            .bg(blue)
+            .add_modifier(Modifier::BOLD),
+        holiday: Style::default()
+            .fg(yellow)
+            .bg(base02)
+            .add_modifier(Modifier::UNDERLINED),
+        season: Style::default()
+            .fg(orange)
+            .bg(base02)
+            .add_modifier(Modifier::BOLD),
+        bookmark: Style::default()
+            .fg(magenta)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        selected: Style::default()
+            .fg(base03)
+            .bg(base1)
+            .add_modifier(Modifier::BOLD),
+        month_header: Style::default().fg(base1).add_modifier(Modifier::BOLD),
+        weekday_header: Style::default().fg(cyan),
+        day: Style::default().fg(base0).bg(base03),
+        surrounding: Style::default().fg(base01),
+        entry: Style::default().fg(cyan).add_modifier(Modifier::BOLD),
+        habit: Style::default().fg(base03).bg(green),
+        added: Style::default().fg(green),
+        removed: Style::default().fg(red),
+        mood: [red, orange, yellow, green, cyan],
+        markdown: MarkdownStyles {
+            heading: Style::default().fg(yellow).add_modifier(Modifier::BOLD),
+            emphasis: Style::default().fg(base1).add_modifier(Modifier::ITALIC),
+            strong: Style::default().fg(base1).add_modifier(Modifier::BOLD),
+            list_marker: Style::default().fg(green),
+            code: Style::default().fg(orange).bg(base02),
+            link: Style::default().fg(blue).add_modifier(Modifier::UNDERLINED),
+            checkbox: Style::default().fg(violet),
+            checkbox_done: Style::default()
+                .fg(base01)
+                .add_modifier(Modifier::CROSSED_OUT),
+            tag: Style::default().fg(magenta),
+            quote: Style::default().fg(base01).add_modifier(Modifier::ITALIC),
+            cursor: Style::default().add_modifier(Modifier::REVERSED),
+        },
+    }
+}
+
+/// Returns the themes that ship with daydream.
+pub fn bundled() -> Vec<Theme> {
+    vec![dark(), light(), high_contrast(), solarized()]
+}
+
+/// A style as written in a theme file.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+struct StyleSpec {
+    fg: Option<String>,
+    bg: Option<String>,
+    modifiers: Vec<String>,
+}
+
+/// The contents of a user theme file.
+///
+/// ```toml
+/// name = "dusk"
+/// base = "solarized"        # optional, defaults to "dark"
+/// mood = ["#c0392b", "#e67e22", "#f1c40f", "#27ae60", "#16a085"]
+///
+/// [styles]
+/// today = { fg = "black", bg = "#ffb86c", modifiers = ["bold"] }
+/// "markdown.heading" = { fg = "magenta", modifiers = ["bold", "underlined"] }
+/// ```
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    name: String,
+    base: Option<String>,
+    mood: Option<[String; 5]>,
+    #[serde(default)]
+    styles: BTreeMap<String, StyleSpec>,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message)
+}
+
+fn parse_color(text: &str) -> io::Result<Color> {
+    Color::from_str(text).map_err(|_| invalid(format!("unknown colour `{}`", text)))
+}
+
+fn parse_modifier(text: &str) -> io::Result<Modifier> {
+    Ok(match text.to_ascii_lowercase().as_str() {
+        "bold" => Modifier::BOLD,
+        "dim" => Modifier::DIM,
+        "italic" => Modifier::ITALIC,
+        "underlined" => Modifier::UNDERLINED,
+        "slow_blink" => Modifier::SLOW_BLINK,
+        "rapid_blink" => Modifier::RAPID_BLINK,
+        "reversed" => Modifier::REVERSED,
+        "hidden" => Modifier::HIDDEN,
+        "crossed_out" => Modifier::CROSSED_OUT,
+        other => return Err(invalid(format!("unknown modifier `{}`", other))),
+    })
+}
+
+impl StyleSpec {
+    fn to_style(&self) -> io::Result<Style> {
+        let mut style = Style::default();
+        if let Some(fg) = &self.fg {
+            style = style.fg(parse_color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(parse_color(bg)?);
+        }
+        for modifier in &self.modifiers {
+            style = style.add_modifier(parse_modifier(modifier)?);
+        }
+        Ok(style)
+    }
+}
+
+impl Theme {
+    /// Returns a copy of the theme using only colours the terminal supports.
+    ///
+    /// Without colour, today and the selected day are reversed so they remain visible.
+    pub fn degraded(&self, support: ColorSupport) -> Theme {
+        let mut theme = self.clone();
+        for key in STYLE_KEYS {
+            if let Some(style) = theme.slot(key) {
+                *style = degrade_style(*style, support);
+            }
+        }
+        for colour in theme.mood.iter_mut() {
+            *colour = degrade_color(*colour, support);
+        }
+        theme.today = emphasise_without_color(theme.today, support);
+        theme.selected = emphasise_without_color(theme.selected, support);
+        theme
+    }
+
+    /// Returns the style stored under a theme file key, e.g. `today` or `markdown.heading`.
+    fn slot(&mut self, key: &str) -> Option<&mut Style> {
+        let markdown = &mut self.markdown;
+        Some(match key {
+            "title_border" => &mut self.title_border,
+            "editor_border" => &mut self.editor_border,
+            "holiday_border" => &mut self.holiday_border,
+            "panel_border" => &mut self.panel_border,
+            "today" => &mut self.today,
+            "holiday" => &mut self.holiday,
+            "season" => &mut self.season,
+            "bookmark" => &mut self.bookmark,
+            "selected" => &mut self.selected,
+            "month_header" => &mut self.month_header,
+            "weekday_header" => &mut self.weekday_header,
+            "day" => &mut self.day,
+            "surrounding" => &mut self.surrounding,
+            "entry" => &mut self.entry,
+            "habit" => &mut self.habit,
+            "added" => &mut self.added,
+            "removed" => &mut self.removed,
+            "markdown.heading" => &mut markdown.heading,
+            "markdown.emphasis" => &mut markdown.emphasis,
+            "markdown.strong" => &mut markdown.strong,
+            "markdown.list_marker" => &mut markdown.list_marker,
+            "markdown.code" => &mut markdown.code,
+            "markdown.link" => &mut markdown.link,
+            "markdown.checkbox" => &mut markdown.checkbox,
+            "markdown.checkbox_done" => &mut markdown.checkbox_done,
+            "markdown.tag" => &mut markdown.tag,
+            "markdown.quote" => &mut markdown.quote,
+            "markdown.cursor" => &mut markdown.cursor,
+            _ => return None,
+        })
+    }
+}
+
+/// Every key accepted by `Theme::slot`.
+const STYLE_KEYS: [&str; 28] = [
+    "title_border",
+    "editor_border",
+    "holiday_border",
+    "panel_border",
+    "today",
+    "holiday",
+    "season",
+    "bookmark",
+    "selected",
+    "month_header",
+    "weekday_header",
+    "day",
+    "surrounding",
+    "entry",
+    "habit",
+    "added",
+    "removed",
+    "markdown.heading",
+    "markdown.emphasis",
+    "markdown.strong",
+    "markdown.list_marker",
+    "markdown.code",
+    "markdown.link",
+    "markdown.checkbox",
+    "markdown.checkbox_done",
+    "markdown.tag",
+    "markdown.quote",
+    "markdown.cursor",
+];
+
+/// Parses a user theme, starting from one of `known` (or the dark theme) and replacing the
+/// styles it lists.
+pub fn parse(text: &str, known: &[Theme]) -> io::Result<Theme> {
+    let file: ThemeFile = toml::from_str(text).map_err(|e| invalid(e.to_string()))?;
+    let mut theme = match &file.base {
+        Some(base) => known
+            .iter()
+            .find(|theme| theme.name == *base)
+            .cloned()
+            .ok_or_else(|| invalid(format!("unknown base theme `{}`", base)))?,
+        None => dark(),
+    };
+    theme.name = file.name;
+    if let Some(mood) = &file.mood {
+        for (slot, colour) in theme.mood.iter_mut().zip(mood) {
+            *slot = parse_color(colour)?;
+        }
+    }
+    for (key, spec) in &file.styles {
+        let style = spec.to_style()?;
+        *theme
+            .slot(key)
+            .ok_or_else(|| invalid(format!("unknown style `{}`", key)))? = style;
+    }
+    Ok(theme)
+}
+
+/// Returns the bundled themes followed by every `*.toml` theme in `dir`.
+///
+/// Files that fail to parse are reported alongside the themes that loaded.
+pub fn load_all(dir: &Path) -> (Vec<Theme>, Vec<(String, io::Error)>) {
+    let mut themes = bundled();
+    let mut errors = Vec::new();
+    let mut paths: Vec<_> = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+    for path in paths {
+        match fs::read_to_string(&path).and_then(|text| parse(&text, &themes)) {
+            Ok(theme) => {
+                themes.retain(|known| known.name != theme.name);
+                themes.push(theme);
+            }
+            Err(e) => errors.push((path.display().to_string(), e)),
+        }
+    }
+    (themes, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_theme() {
+        let text = r##"
+            name = "dusk"
+            base = "solarized"
+            mood = ["red", "#ff8800", "yellow", "green", "cyan"]
+
+            [styles]
+            today = { fg = "black", bg = "#ffb86c", modifiers = ["bold"] }
+            "markdown.heading" = { fg = "magenta" }
+        "##;
+        let theme = parse(text, &bundled()).unwrap();
+        assert_eq!(theme.name, "dusk");
+        assert_eq!(
+            theme.today,
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Rgb(255, 184, 108))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(theme.markdown.heading, Style::default().fg(Color::Magenta));
+        assert_eq!(theme.mood[1], Color::Rgb(255, 136, 0));
+        assert_eq!(theme.day, solarized().day);
+
+        assert!(parse("name = \"x\"\n[styles]\nnope = {}", &bundled()).is_err());
+        assert!(parse("name = \"x\"\nbase = \"missing\"", &bundled()).is_err());
+    }
+}
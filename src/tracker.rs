@@ -0,0 +1,251 @@
+use crate::glyphs::Glyphs;
+use crate::journal::{EntryMeta, JournalIndex};
+use crate::theme::Theme;
+use core::fmt;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{calendar::CalendarEventStore, Block, Borders, Paragraph, Wrap},
+};
+use time::Date;
+
+/// Habits offered in the tracker form, in display order.
+pub const HABITS: [&str; 3] = ["exercise", "meditation", "reading"];
+
+/// Highest mood score that can be recorded.
+pub const MAX_MOOD: u8 = 5;
+
+/// State of the mood and habit form shown next to the editor.
+#[derive(Default, Clone, Debug)]
+pub struct Tracker {
+    /// Whether the form is shown and receives key input.
+    pub visible: bool,
+    /// Selected row: 0 is the mood, the rest are `HABITS`.
+    pub selected: usize,
+    /// Values being edited for the selected date.
+    pub meta: EntryMeta,
+    /// Set when the sidecar could not be read, so saving leaves it untouched.
+    pub unreadable: bool,
+}
+
+impl Tracker {
+    /// Creates a hidden form holding the given metadata.
+    pub fn new(meta: EntryMeta) -> Self {
+        Tracker {
+            visible: false,
+            selected: 0,
+            meta,
+            unreadable: false,
+        }
+    }
+
+    /// Shows or hides the form.
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Applies a key press to the form.
+    pub fn input(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.visible = false,
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(HABITS.len()),
+            KeyCode::Left if self.selected == 0 => {
+                self.meta.mood = match self.meta.mood {
+                    Some(mood) if mood > 1 => Some(mood - 1),
+                    _ => None,
+                }
+            }
+            KeyCode::Right if self.selected == 0 => {
+                self.meta.mood = Some(self.meta.mood.map_or(1, |mood| (mood + 1).min(MAX_MOOD)))
+            }
+            KeyCode::Char(c) if self.selected == 0 && c.is_ascii_digit() => {
+                let mood = c.to_digit(10).unwrap_or(0) as u8;
+                self.meta.mood = (1..=MAX_MOOD).contains(&mood).then_some(mood);
+            }
+            KeyCode::Backspace | KeyCode::Delete if self.selected == 0 => self.meta.mood = None,
+            KeyCode::Char(' ') | KeyCode::Enter if self.selected > 0 => {
+                let name = HABITS[self.selected - 1];
+                let done = !self.meta.habit(name);
+                self.meta.habits.insert(name.to_string(), done);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Draws the tracker form in the given area.
+pub fn draw_tracker(
+    tracker: &Tracker,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let mood = tracker.meta.mood.unwrap_or(0);
+    let mut lines = vec![Line::from(format!(
+        "mood  {}{}",
+        glyphs.filled.repeat(mood as usize),
+        glyphs.empty.repeat((MAX_MOOD - mood) as usize)
+    ))];
+    lines.extend(HABITS.iter().map(|name| {
+        let mark = if tracker.meta.habit(name) { "x" } else { " " };
+        Line::from(format!("[{}] {}", mark, name))
+    }));
+    lines[tracker.selected].patch_style(Style::default().add_modifier(Modifier::REVERSED));
+    lines.push(Line::from(""));
+    lines.push(Line::styled(
+        format!("{} or 1-5 mood, {} toggle", glyphs.left_right, glyphs.space),
+        Style::default().add_modifier(Modifier::DIM),
+    ));
+    if tracker.unreadable {
+        lines.push(Line::from(
+            "the saved data could not be read, so changes here are not kept",
+        ));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+            Block::default()
+                .title("mood & habits")
+                .border_style(theme.panel_border)
+                .borders(Borders::all())
+                .border_set(glyphs.panel_border),
+        ),
+        area,
+    );
+}
+
+/// Which tracked data the calendar colours days by.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Overlay {
+    #[default]
+    Entries,
+    Mood,
+    Habit(usize),
+}
+
+impl Overlay {
+    /// Returns the overlay that follows this one, wrapping back to `Entries`.
+    pub fn next(self) -> Self {
+        match self {
+            Overlay::Entries => Overlay::Mood,
+            Overlay::Mood => Overlay::Habit(0),
+            Overlay::Habit(i) if i + 1 < HABITS.len() => Overlay::Habit(i + 1),
+            Overlay::Habit(_) => Overlay::Entries,
+        }
+    }
+}
+
+impl fmt::Display for Overlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Overlay::Entries => write!(f, "entries"),
+            Overlay::Mood => write!(f, "mood"),
+            Overlay::Habit(i) => write!(f, "habit: {}", HABITS[i]),
+        }
+    }
+}
+
+/// Returns the style used for a mood score, from the theme's lowest (1) to highest (5) colour.
+///
+/// When the theme has no colours left, low moods are dimmed and high moods emboldened.
+fn mood_style(theme: &Theme, mood: u8) -> Style {
+    let colour = theme.mood[(mood.clamp(1, MAX_MOOD) - 1) as usize];
+    match colour {
+        Color::Reset if mood < 3 => Style::default().add_modifier(Modifier::DIM),
+        Color::Reset => Style::default().add_modifier(Modifier::BOLD),
+        _ => Style::default().fg(Color::Black).bg(colour),
+    }
+}
+
+/// Layers the overlay's styles on top of the existing event styles, leaving `skip` untouched.
+pub fn apply_overlay(
+    overlay: Overlay,
+    theme: &Theme,
+    index: &JournalIndex,
+    events: &mut CalendarEventStore,
+    skip: Date,
+) {
+    let styled: Vec<(Date, Style)> = match overlay {
+        Overlay::Entries => index
+            .dates
+            .iter()
+            .map(|date| (*date, theme.entry))
+            .collect(),
+        Overlay::Mood => index
+            .meta
+            .iter()
+            .filter_map(|(date, meta)| meta.mood.map(|mood| (*date, mood_style(theme, mood))))
+            .collect(),
+        Overlay::Habit(i) => index
+            .meta
+            .iter()
+            .filter(|(_, meta)| meta.habit(HABITS[i]))
+            .map(|(date, _)| (*date, theme.habit))
+            .collect(),
+    };
+    for (date, style) in styled.into_iter().filter(|(date, _)| *date != skip) {
+        let base = events.0.get(&date).copied().unwrap_or_default();
+        events.add(date, base.patch(style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::parse_date;
+
+    #[test]
+    fn test_overlays() {
+        let mut overlay = Overlay::default();
+        let mut seen = vec![overlay];
+        for _ in 0..HABITS.len() + 1 {
+            overlay = overlay.next();
+            seen.push(overlay);
+        }
+        assert_eq!(seen[1], Overlay::Mood);
+        assert_eq!(seen[2], Overlay::Habit(0));
+        assert_eq!(*seen.last().unwrap(), Overlay::Habit(HABITS.len() - 1));
+        assert_eq!(overlay.next(), Overlay::Entries);
+
+        let theme = Theme::default();
+        let date = |text| parse_date(text).unwrap();
+        let mut index = JournalIndex::default();
+        index.dates.extend([date("2024-03-12"), date("2024-03-13")]);
+        let mut meta = EntryMeta::default();
+        meta.habits.insert(HABITS[1].to_string(), true);
+        index.meta.insert(date("2024-03-12"), meta);
+
+        let mut events = CalendarEventStore::default();
+        apply_overlay(
+            Overlay::Entries,
+            &theme,
+            &index,
+            &mut events,
+            date("2024-03-13"),
+        );
+        assert_eq!(events.0.get(&date("2024-03-12")), Some(&theme.entry));
+        // the selected date is left to the selection style
+        assert!(!events.0.contains_key(&date("2024-03-13")));
+
+        let mut events = CalendarEventStore::default();
+        apply_overlay(
+            Overlay::Habit(1),
+            &theme,
+            &index,
+            &mut events,
+            date("2024-03-13"),
+        );
+        assert_eq!(events.0.get(&date("2024-03-12")), Some(&theme.habit));
+        let mut events = CalendarEventStore::default();
+        apply_overlay(
+            Overlay::Habit(0),
+            &theme,
+            &index,
+            &mut events,
+            date("2024-03-13"),
+        );
+        assert!(events.0.is_empty());
+    }
+}
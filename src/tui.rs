@@ -31,6 +31,16 @@ impl Render for Tui {
                 // Assuming there's a render_journal_sort function
                 self.terminal.draw(|_frame| unimplemented!())?;
             } // Handle other modes if necessary
+            Mode::LOCKED => {
+                self.terminal
+                    .draw(|frame| ui::render_lock_screen(app, frame))?;
+            }
+            Mode::HISTORY => {
+                self.terminal.draw(|frame| ui::render_history(app, frame))?;
+            }
+            Mode::VIEWER => {
+                self.terminal.draw(|frame| ui::render_viewer(app, frame))?;
+            }
         }
         Ok(())
     }
@@ -86,14 +96,14 @@ impl Tui {
     // draws terminal interface -> renders pertinent widgets
     pub fn draw(&mut self, app: &mut AppState) -> Result<(), Box<dyn std::error::Error>> {
         match self.render(app) {
-            Ok(()) => return Ok(()),
+            Ok(()) => Ok(()),
             Err(_) => Ok(()),
         }
     }
     // frame auto resizes, this method is only for idiomatic purposes
     pub fn resize(&mut self, app: &mut AppState) -> Result<(), Box<dyn std::error::Error>> {
         match self.render(app) {
-            Ok(()) => return Ok(()),
+            Ok(()) => Ok(()),
             Err(_) => Ok(()),
         }
     }
@@ -1,76 +1,219 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     prelude::{Alignment, Frame},
-    style::{Style, Stylize},
-    widgets::{Block, BorderType, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph},
 };
 
 use crate::app::AppState;
-use crate::calendar::draw_calendar;
-use crate::editor::draw_editor;
+use crate::bookmarks::{draw_picker, Bookmarks};
+use crate::calendar::{draw_calendar, split_screen};
+use crate::editor::{self, draw_editor};
+use crate::help::draw_help;
+use crate::history::draw_history;
+use crate::onthisday::{draw_side_panels, PANEL_WIDTH};
+use crate::prompt::draw_prompt;
+use crate::viewer::{draw_viewer, overlay_area};
 
 pub fn render_journal_entry(app: &mut AppState, frame: &mut Frame) {
     let frame_size = frame.size();
-
-    let constraints = vec![
-        Constraint::Percentage(12), // Adjust this value to allocate more space for the title
-        Constraint::Percentage(85),
-    ];
-
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(frame_size);
-
-    let title_area = layout[0];
-    let _editor_area = layout[1];
+    let (title_area, _) = editor::split_screen(frame_size);
 
     draw_editor(app, frame);
 
     // Render the title
     frame.render_widget(
         Paragraph::new(
-            "write/edit an entry 
-        save and exit with `Ctrl-S` or `Esc`
-        to exit without saving use `Ctrl-C`",
+            "`Ctrl-S` saves and goes back, `Ctrl-C` quits unsaved, `F1` lists every key",
         )
         .block(
             Block::default()
-                .title(format!("『daydream』- editor"))
-                .border_style(Style::new().light_green())
+                .title(format!(
+                    "{}daydream{}- editor",
+                    app.glyphs.open, app.glyphs.close
+                ))
+                .border_style(app.theme.title_border)
                 .borders(Borders::all())
-                .border_type(BorderType::QuadrantOutside)
+                .border_set(app.glyphs.title_border)
                 .borders(Borders::all())
                 .title_alignment(Alignment::Left),
         )
         .alignment(Alignment::Center),
         title_area,
     );
+
+    if let Some(prompt) = &app.prompt {
+        draw_prompt(prompt, &app.theme, app.glyphs, frame, frame_size);
+    }
+    if let Some(viewer) = app.viewer.as_mut() {
+        draw_viewer(
+            viewer,
+            &app.theme,
+            app.glyphs,
+            frame,
+            overlay_area(frame_size),
+        );
+    }
+    if app.help {
+        draw_help(app.mode, &app.theme, app.glyphs, frame, frame_size);
+    }
 }
 
 pub fn render_journal_calendar(app: &mut AppState, frame: &mut Frame) {
+    let (title_area, _) = split_screen(frame.size());
+
+    draw_calendar(app, frame);
+
+    // Render the title
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{} select a day with the arrow keys, `{}` to read it, `?` for every key\n`v` switches the view ({}), `o` the overlay ({}), `q` quits",
+            app.glyphs.wave, app.glyphs.enter, app.calendar_view, app.overlay
+        ))
+        .block(
+            Block::default()
+                .title(format!(
+                    "{}daydream{}- {}",
+                    app.glyphs.open, app.glyphs.close, app.theme.name
+                ))
+                .border_style(app.theme.title_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.title_border),
+        )
+        .alignment(Alignment::Center),
+        title_area,
+    );
+
+    if let Some(picker) = &app.bookmark_picker {
+        let empty = Bookmarks::new();
+        let bookmarks = app.index.as_ref().map_or(&empty, |index| &index.bookmarks);
+        draw_picker(
+            picker,
+            bookmarks,
+            &app.theme,
+            app.glyphs,
+            frame,
+            frame.size(),
+        );
+    }
+    if let Some(prompt) = &app.prompt {
+        draw_prompt(prompt, &app.theme, app.glyphs, frame, frame.size());
+    }
+    if let Some(viewer) = app.viewer.as_mut() {
+        draw_viewer(
+            viewer,
+            &app.theme,
+            app.glyphs,
+            frame,
+            overlay_area(frame.size()),
+        );
+    }
+    if app.help {
+        draw_help(app.mode, &app.theme, app.glyphs, frame, frame.size());
+    }
+}
+
+pub fn render_lock_screen(app: &mut AppState, frame: &mut Frame) {
     let frame_size = frame.size();
 
-    let constraints = vec![
-        Constraint::Percentage(15), // Adjust this value to allocate more space for the titl
-        Constraint::Percentage(85), // Adjust this value to allocate more space for the titl
-    ];
+    frame.render_widget(
+        Paragraph::new(format!(
+            "daydream is locked {}\nunlock with `{}`, or quit with `Esc`",
+            app.glyphs.shrug, app.glyphs.enter
+        ))
+        .block(
+            Block::default()
+                .title(format!(
+                    "{}daydream{}- locked",
+                    app.glyphs.open, app.glyphs.close
+                ))
+                .border_style(app.theme.title_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.title_border),
+        )
+        .alignment(Alignment::Center),
+        frame_size,
+    );
+
+    if let Some(prompt) = &app.prompt {
+        draw_prompt(prompt, &app.theme, app.glyphs, frame, frame_size);
+    }
+}
+
+pub fn render_history(app: &mut AppState, frame: &mut Frame) {
+    let frame_size = frame.size();
 
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(constraints)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
         .split(frame_size);
 
-    let title_area = layout[0];
-    // let calendar_area = layout[1];
+    frame.render_widget(
+        Paragraph::new(format!(
+            "select a version with the arrow keys, `{}` restores it, `?` for every key",
+            app.glyphs.enter
+        ))
+        .block(
+            Block::default()
+                .title(format!(
+                    "{}daydream{}- history",
+                    app.glyphs.open, app.glyphs.close
+                ))
+                .border_style(app.theme.title_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.title_border),
+        )
+        .alignment(Alignment::Center),
+        layout[0],
+    );
 
-    draw_calendar(app, frame);
+    if let Some(history) = &app.history {
+        draw_history(history, &app.theme, app.glyphs, frame, layout[1]);
+    }
+    if app.help {
+        draw_help(app.mode, &app.theme, app.glyphs, frame, frame_size);
+    }
+}
+
+pub fn render_viewer(app: &mut AppState, frame: &mut Frame) {
+    let frame_size = frame.size();
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)])
+        .split(frame_size);
 
-    // Render the title
     frame.render_widget(
-        Paragraph::new("Press `Esc`, `Ctrl-C`, or `q` to halt program. ﾉ(._.ﾉ)\nSelect a day using the arrow keys.\n(/ ･〰･)／ Use the `↵` key to select an entry to edit.")
-            .block(Block::default().title("『daydream』").border_style(Style::new().light_green()).borders(Borders::all()).border_type(BorderType::QuadrantOutside))
-            .alignment(Alignment::Center),
-        title_area,
+        Paragraph::new(
+            "arrows scroll, `/` searches, `e` edits, `Esc` goes back, `?` for every key",
+        )
+        .block(
+            Block::default()
+                .title(format!(
+                    "{}daydream{}- viewer",
+                    app.glyphs.open, app.glyphs.close
+                ))
+                .border_style(app.theme.title_border)
+                .borders(Borders::all())
+                .border_set(app.glyphs.title_border),
+        )
+        .alignment(Alignment::Center),
+        layout[0],
     );
+
+    let mut viewer_area = layout[1];
+    if app.side_panels_shown() {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(PANEL_WIDTH)])
+            .split(viewer_area);
+        viewer_area = columns[0];
+        draw_side_panels(app, frame, columns[1], "1-9", "f");
+    }
+
+    if let Some(viewer) = app.viewer.as_mut() {
+        draw_viewer(viewer, &app.theme, app.glyphs, frame, viewer_area);
+    }
+    if app.help {
+        draw_help(app.mode, &app.theme, app.glyphs, frame, frame_size);
+    }
 }
@@ -1,6 +1,11 @@
 use crate::{
-    app::{AppState, Mode},
+    app::{AppState, CalendarPrompt, Mode},
+    attachments::SelectionEvent,
+    bookmarks::PickerEvent,
+    calendar::week_start,
     editor::update_editor,
+    prompt::PromptEvent,
+    viewer::ViewerEvent,
 };
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use time::{Date, Duration};
@@ -11,10 +16,22 @@ pub trait UpdateEvent {
 }
 impl UpdateEvent for KeyEvent {
     fn update(&self, app: &mut AppState) {
+        // the key list closes on any key, which does nothing else
+        if app.help {
+            app.help = false;
+            return;
+        }
+        if self.code == KeyCode::F(1) && app.mode != Mode::LOCKED {
+            app.help = true;
+            return;
+        }
         match app.mode {
             Mode::CALENDAR => self.process_calendar_input(app),
             Mode::EDITOR => self.process_editor_input(app),
             Mode::SORT => self.process_sort_input(app),
+            Mode::LOCKED => self.process_locked_input(app),
+            Mode::HISTORY => self.process_history_input(app),
+            Mode::VIEWER => self.process_viewer_input(app),
         }
     }
 }
@@ -22,17 +39,57 @@ pub trait EventProcessorExtension {
     fn process_calendar_input(&self, app: &mut AppState);
     fn process_editor_input(&self, app: &mut AppState);
     fn process_sort_input(&self, app: &mut AppState);
+    fn process_locked_input(&self, app: &mut AppState);
+    fn process_history_input(&self, app: &mut AppState);
+    fn process_viewer_input(&self, app: &mut AppState);
+    fn process_attachment_input(&self, app: &mut AppState) -> bool;
     fn convert_to_editor_input(&self) -> Option<Input>;
 }
 impl EventProcessorExtension for KeyEvent {
     fn process_calendar_input(&self, app: &mut AppState) {
+        if matches!(self.code, KeyCode::Char('c') | KeyCode::Char('C'))
+            && self.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            app.quit();
+            return;
+        }
+        if let Some(viewer) = app.viewer.as_mut() {
+            match viewer.input(self) {
+                ViewerEvent::Close => app.close_viewer(false),
+                ViewerEvent::Edit => app.close_viewer(true),
+                ViewerEvent::Pending => {}
+            }
+            return;
+        }
+        if let Some(prompt) = app.prompt.as_mut() {
+            match prompt.input(self) {
+                PromptEvent::Submit(text) => match app.calendar_prompt {
+                    CalendarPrompt::GoTo => app.go_to(&text),
+                    CalendarPrompt::Bookmark => app.set_bookmark(&text),
+                    CalendarPrompt::UnlockEntry => app.unlock_entry(&text),
+                },
+                PromptEvent::Cancel => app.prompt = None,
+                PromptEvent::Pending => {}
+            }
+            return;
+        }
+        if let Some(mut picker) = app.bookmark_picker.take() {
+            match picker.input(self, &app.journal_index().bookmarks) {
+                PickerEvent::Jump(date) => app.selected_date = date,
+                PickerEvent::Remove(date) => {
+                    app.bookmark_picker = Some(picker);
+                    app.remove_bookmark(date);
+                }
+                PickerEvent::Close => {}
+                PickerEvent::Pending => app.bookmark_picker = Some(picker),
+            }
+            return;
+        }
+        if self.process_attachment_input(app) {
+            return;
+        }
         match self.code {
             KeyCode::Esc | KeyCode::Char('q') => app.quit(),
-            KeyCode::Char('c') | KeyCode::Char('C')
-                if self.modifiers.contains(KeyModifiers::CONTROL) =>
-            {
-                app.quit()
-            }
             KeyCode::Right => {
                 if self.modifiers.contains(KeyModifiers::CONTROL) {
                     app.selected_date = app
@@ -79,16 +136,81 @@ impl EventProcessorExtension for KeyEvent {
                         .unwrap_or(Date::MIN)
                 }
             }
-            KeyCode::Enter => {
-                app.mode = Mode::EDITOR;
-                slog::info!(app.logger, "Mode Changed"; "mode" => %app.mode);
+            KeyCode::Home | KeyCode::End => {
+                let start = week_start(app.selected_date, app.config.first_weekday.into());
+                app.selected_date = if self.code == KeyCode::Home {
+                    start
+                } else {
+                    start.checked_add(Duration::days(6)).unwrap_or(Date::MAX)
+                };
+            }
+            KeyCode::Enter => app.open_selected(),
+            KeyCode::Char('e') => app.edit_selected(),
+            KeyCode::Char('g') => app.open_goto(),
+            KeyCode::Char('t') => app.go_to_today(),
+            KeyCode::Char('b') => app.open_bookmark_prompt(),
+            KeyCode::Char('B') => app.open_bookmarks(),
+            KeyCode::Char('p') => app.on_this_day = !app.on_this_day,
+            KeyCode::Char('l') => app.backlinks = !app.backlinks,
+            KeyCode::Char('f') => app.select_attachment(),
+            KeyCode::Char(c @ '1'..='9') => app.open_listed(c as usize - '0' as usize),
+            KeyCode::Char('T') => app.next_theme(),
+            KeyCode::Char('L') => app.lock_screen(),
+            KeyCode::Char('h') => app.open_history(),
+            KeyCode::Char('?') => app.help = true,
+            KeyCode::Char('o') => {
+                app.overlay = app.overlay.next();
+                slog::info!(app.logger, "Overlay Changed"; "overlay" => %app.overlay);
+            }
+            KeyCode::Char('v') => {
+                app.calendar_view = app.calendar_view.next();
+                slog::info!(app.logger, "View Changed"; "view" => %app.calendar_view);
             }
             _ => {}
         }
     }
     fn process_editor_input(&self, app: &mut AppState) {
+        // the entry being edited stays open, so the viewer over it can only be closed
+        if let Some(viewer) = app.viewer.as_mut() {
+            if viewer.input(self) != ViewerEvent::Pending {
+                app.viewer = None;
+            }
+            return;
+        }
+        // a locked entry never takes keystrokes, however the editor was reached
+        if app.is_sealed(app.selected_date) {
+            app.initialized = false;
+            app.edit_selected();
+            return;
+        }
+        if let Some(prompt) = app.prompt.as_mut() {
+            match prompt.input(self) {
+                PromptEvent::Submit(text) => app.attach(&text),
+                PromptEvent::Cancel => app.prompt = None,
+                PromptEvent::Pending => {}
+            }
+            return;
+        }
+        if self.process_attachment_input(app) {
+            return;
+        }
+        if let KeyCode::Char(c @ '1'..='9') = self.code {
+            if self.modifiers.contains(KeyModifiers::ALT) {
+                app.open_listed(c as usize - '0' as usize);
+                return;
+            }
+        }
         if self.modifiers.contains(KeyModifiers::CONTROL) {
             match self.code {
+                KeyCode::Char('t') | KeyCode::Char('T') => app.tracker.toggle(),
+                KeyCode::Char('p') | KeyCode::Char('P') => app.preview = !app.preview,
+                KeyCode::Char('o') | KeyCode::Char('O') => app.on_this_day = !app.on_this_day,
+                KeyCode::Char('b') | KeyCode::Char('B') => app.backlinks = !app.backlinks,
+                KeyCode::Char('g') | KeyCode::Char('G') => app.follow_link(),
+                KeyCode::Char('a') | KeyCode::Char('A') => app.open_attach_prompt(),
+                KeyCode::Char('f') | KeyCode::Char('F') => app.select_attachment(),
+                KeyCode::Char('l') | KeyCode::Char('L') => app.lock_screen(),
+                KeyCode::Char('r') | KeyCode::Char('R') => app.open_history(),
                 KeyCode::Char('s') | KeyCode::Char('S') => {
                     app.save();
                     app.mode = Mode::CALENDAR;
@@ -97,6 +219,8 @@ impl EventProcessorExtension for KeyEvent {
                 KeyCode::Char('c') | KeyCode::Char('C') => app.quit(),
                 _ => {}
             }
+        } else if app.tracker.visible {
+            app.tracker.input(self);
         } else {
             if self.code == KeyCode::Esc {
                 app.save();
@@ -114,6 +238,88 @@ impl EventProcessorExtension for KeyEvent {
         todo!()
     }
 
+    fn process_locked_input(&self, app: &mut AppState) {
+        if self.code == KeyCode::Char('c') && self.modifiers.contains(KeyModifiers::CONTROL) {
+            app.quit();
+            return;
+        }
+        let Some(prompt) = app.prompt.as_mut() else {
+            return;
+        };
+        match prompt.input(self) {
+            PromptEvent::Submit(passphrase) => app.unlock(&passphrase),
+            PromptEvent::Cancel => app.quit(),
+            PromptEvent::Pending => {}
+        }
+    }
+
+    fn process_history_input(&self, app: &mut AppState) {
+        let Some(history) = app.history.as_mut() else {
+            app.mode = Mode::CALENDAR;
+            return;
+        };
+        let vault = app.vault.as_ref();
+        match self.code {
+            KeyCode::Esc | KeyCode::Char('q') => app.close_history(),
+            KeyCode::Char('c') if self.modifiers.contains(KeyModifiers::CONTROL) => app.quit(),
+            KeyCode::Up => history.select(-1, &app.entries_dir, vault),
+            KeyCode::Down => history.select(1, &app.entries_dir, vault),
+            KeyCode::PageUp => history.scroll = history.scroll.saturating_sub(10),
+            KeyCode::PageDown => history.scroll = history.scroll.saturating_add(10),
+            KeyCode::Enter => app.restore_version(),
+            KeyCode::Char('?') => app.help = true,
+            _ => {}
+        }
+    }
+
+    fn process_viewer_input(&self, app: &mut AppState) {
+        if self.code == KeyCode::Char('c') && self.modifiers.contains(KeyModifiers::CONTROL) {
+            app.quit();
+            return;
+        }
+        if app.viewer.is_none() {
+            app.mode = Mode::CALENDAR;
+            return;
+        }
+        if self.process_attachment_input(app) {
+            return;
+        }
+        let Some(viewer) = app.viewer.as_mut() else {
+            return;
+        };
+        match self.code {
+            KeyCode::Char('L') if !viewer.searching => app.lock_screen(),
+            KeyCode::Char('p') if !viewer.searching => app.on_this_day = !app.on_this_day,
+            KeyCode::Char('l') if !viewer.searching => app.backlinks = !app.backlinks,
+            KeyCode::Char('f') if !viewer.searching => app.select_attachment(),
+            KeyCode::Char('?') if !viewer.searching => app.help = true,
+            KeyCode::Char(c @ '1'..='9') if !viewer.searching => {
+                app.open_listed(c as usize - '0' as usize)
+            }
+            _ => match viewer.input(self) {
+                ViewerEvent::Close => app.close_viewer(false),
+                ViewerEvent::Edit => app.close_viewer(true),
+                ViewerEvent::Pending => {}
+            },
+        }
+    }
+
+    fn process_attachment_input(&self, app: &mut AppState) -> bool {
+        let Some(mut selection) = app.attachment_selection.take() else {
+            return false;
+        };
+        let count = app.attachment_names(app.selected_date).len();
+        match selection.input(self, count) {
+            SelectionEvent::Open(i) => {
+                app.attachment_selection = Some(selection);
+                app.open_attachment(i);
+            }
+            SelectionEvent::Close => {}
+            SelectionEvent::Pending => app.attachment_selection = Some(selection),
+        }
+        true
+    }
+
     fn convert_to_editor_input(&self) -> Option<Input> {
         Some(Input {
             key: match self.code {
@@ -142,16 +348,13 @@ impl EventProcessorExtension for KeyEvent {
 }
 impl UpdateEvent for MouseEvent {
     fn update(&self, _app: &mut AppState) {
-        match self.kind {
-            MouseEventKind::Down(MouseButton::Left) => {
-                // let (_x, _y): (i16, i16) = (self.column as i16, self.row as i16);
-                // // map clicked coords to a calendar date
-                // // let clicked_date = map_to_date(app, x, y);
-                // // update AppState with selected date
-                // app.selected_date = OffsetDateTime::now_utc().date();
-                // app.mode = Mode::EDITOR;
-            }
-            _ => {}
+        if let MouseEventKind::Down(MouseButton::Left) = self.kind {
+            // let (_x, _y): (i16, i16) = (self.column as i16, self.row as i16);
+            // // map clicked coords to a calendar date
+            // // let clicked_date = map_to_date(app, x, y);
+            // // update AppState with selected date
+            // app.selected_date = OffsetDateTime::now_utc().date();
+            // app.mode = Mode::EDITOR;
         }
     }
 }
@@ -160,7 +363,6 @@ mod tests {
     use crate::app::{AppState, Mode};
     use crate::update::UpdateEvent;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-    use time::OffsetDateTime;
     use tui_textarea::TextArea;
     pub fn get_last_character(text_area: &TextArea) -> Option<char> {
         text_area.lines().last()?.chars().last()
@@ -171,7 +373,7 @@ mod tests {
 
         // Test all key codes and modifiers in CALENDAR mode
         app.mode = Mode::CALENDAR;
-        for (key_code, modifier) in vec![
+        for (key_code, modifier) in [
             (KeyCode::Esc, KeyModifiers::NONE),
             (KeyCode::Char('q'), KeyModifiers::NONE),
             (KeyCode::Char('c'), KeyModifiers::NONE),
@@ -184,6 +386,7 @@ mod tests {
             (KeyCode::Char('c'), KeyModifiers::CONTROL),
             (KeyCode::Char('C'), KeyModifiers::CONTROL),
         ] {
+            let previous_date = app.selected_date;
             let key_event = KeyEvent::new(key_code, modifier);
             key_event.update(&mut app);
             // Add assertions here based on expected behavior
@@ -191,11 +394,11 @@ mod tests {
                 (KeyCode::Esc, _) | (KeyCode::Char('q'), _) => assert!(app.quit_flag),
                 (KeyCode::Char('c'), KeyModifiers::CONTROL)
                 | (KeyCode::Char('C'), KeyModifiers::CONTROL) => assert!(app.quit_flag),
-                (KeyCode::Right, _) | (KeyCode::Up, _) => {
-                    assert!(app.selected_date > OffsetDateTime::now_local().unwrap().date())
+                (KeyCode::Right, _) | (KeyCode::Down, _) => {
+                    assert!(app.selected_date > previous_date)
                 }
-                (KeyCode::Left, _) | (KeyCode::Down, _) => {
-                    assert!(app.selected_date < OffsetDateTime::now_local().unwrap().date())
+                (KeyCode::Left, _) | (KeyCode::Up, _) => {
+                    assert!(app.selected_date < previous_date)
                 }
                 (KeyCode::Enter, _) => assert_eq!(app.mode, Mode::EDITOR),
                 _ => {}
@@ -203,8 +406,7 @@ mod tests {
         }
 
         // Test all key codes and modifiers in EDITOR mode
-        app.mode = Mode::EDITOR;
-        for (key_code, modifier) in vec![
+        for (key_code, modifier) in [
             (KeyCode::Char('S'), KeyModifiers::CONTROL),
             (KeyCode::Char('s'), KeyModifiers::CONTROL),
             (KeyCode::Char('C'), KeyModifiers::CONTROL),
@@ -219,6 +421,7 @@ mod tests {
             (KeyCode::Home, KeyModifiers::NONE),
             (KeyCode::End, KeyModifiers::NONE),
         ] {
+            app.mode = Mode::EDITOR;
             let key_event = KeyEvent::new(key_code, modifier);
             key_event.update(&mut app);
             // Add assertions here based on expected behavior
@@ -238,7 +441,7 @@ mod tests {
 
         // Test all key codes and modifiers in SORT mode
         app.mode = Mode::SORT;
-        for (key_code, modifier) in vec![/* Add all relevant key codes and modifiers here */] {
+        for (key_code, modifier) in [/* Add all relevant key codes and modifiers here */] {
             let key_event = KeyEvent::new(key_code, modifier);
             key_event.update(&mut app);
             // Add assertions here based on expected behavior
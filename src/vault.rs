@@ -0,0 +1,221 @@
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Name of the file, inside the entries directory, that marks an encrypted journal.
+pub const VAULT_FILE: &str = ".daydream-vault";
+
+/// Prefix of every encrypted file, whose contents are bound to its file name.
+const SEALED_MAGIC: &[u8] = b"DDSEALED2\n";
+
+/// Prefix of the vault file.
+const VAULT_MAGIC: &[u8] = b"DDVAULT1\n";
+
+/// Plaintext sealed into the vault file to check a passphrase against.
+const CHECK: &[u8] = b"daydream";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+
+/// Key material for an encrypted journal.
+///
+/// Files are sealed with XChaCha20-Poly1305 under a key derived from the passphrase with
+/// Argon2id, so any change to a sealed file is detected when it is opened. The file's name is
+/// authenticated along with it (for attachments, their path in the entries directory), so a
+/// sealed file copied over another one does not open either.
+pub struct Vault {
+    cipher: XChaCha20Poly1305,
+}
+
+/// Returns the path of the vault file for an entries directory.
+pub fn vault_path(dir: &Path) -> PathBuf {
+    dir.join(VAULT_FILE)
+}
+
+/// Returns true when the journal in `dir` is encrypted.
+pub fn exists(dir: &Path) -> bool {
+    vault_path(dir).is_file()
+}
+
+/// Returns true when the bytes were produced by `Vault::seal`.
+pub fn is_sealed(data: &[u8]) -> bool {
+    data.starts_with(SEALED_MAGIC)
+}
+
+/// Returns the name the contents of a file are sealed with: its file name.
+pub fn sealed_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn derive(passphrase: &str, salt: &[u8]) -> io::Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| io::Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    key.fill(0);
+    Ok(cipher)
+}
+
+impl Vault {
+    /// Creates a vault for the journal in `dir`, protected by `passphrase`.
+    pub fn create(dir: &Path, passphrase: &str) -> io::Result<Vault> {
+        if exists(dir) {
+            return Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                "journal is already encrypted",
+            ));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let vault = Vault {
+            cipher: derive(passphrase, &salt)?,
+        };
+        let mut contents = VAULT_MAGIC.to_vec();
+        contents.extend_from_slice(&salt);
+        contents.extend(vault.seal(CHECK, VAULT_FILE)?);
+        fs::create_dir_all(dir)?;
+        fs::write(vault_path(dir), contents)?;
+        Ok(vault)
+    }
+
+    /// Opens the vault of the journal in `dir`, failing if the passphrase is wrong.
+    pub fn unlock(dir: &Path, passphrase: &str) -> io::Result<Vault> {
+        let contents = fs::read(vault_path(dir))?;
+        let rest = contents
+            .strip_prefix(VAULT_MAGIC)
+            .filter(|rest| rest.len() > SALT_LEN)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "damaged vault file"))?;
+        let (salt, check) = rest.split_at(SALT_LEN);
+        let vault = Vault {
+            cipher: derive(passphrase, salt)?,
+        };
+        match vault.open(check, VAULT_FILE) {
+            Ok(plain) if plain == CHECK => Ok(vault),
+            _ => Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "wrong passphrase",
+            )),
+        }
+    }
+
+    /// Encrypts and authenticates `plain` as the contents of the file called `name`.
+    pub fn seal(&self, plain: &[u8], name: &str) -> io::Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: plain,
+            aad: name.as_bytes(),
+        };
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        let mut sealed = SEALED_MAGIC.to_vec();
+        sealed.extend_from_slice(&nonce);
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    /// Decrypts bytes produced by `seal` for the file called `name`, failing if they were
+    /// tampered with or belong to another file.
+    pub fn open(&self, sealed: &[u8], name: &str) -> io::Result<Vec<u8>> {
+        let rest = sealed
+            .strip_prefix(SEALED_MAGIC)
+            .filter(|rest| rest.len() >= NONCE_LEN)
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "not an encrypted file"))?;
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: name.as_bytes(),
+        };
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    "encrypted file is damaged or was modified",
+                )
+            })
+    }
+}
+
+/// Encodes the contents of the file called `name` for disk, sealing them when the journal is
+/// encrypted.
+pub fn encode(vault: Option<&Vault>, name: &str, plain: &[u8]) -> io::Result<Vec<u8>> {
+    match vault {
+        Some(vault) => vault.seal(plain, name),
+        None => Ok(plain.to_vec()),
+    }
+}
+
+/// Decodes the contents of the file called `name` read from disk. An encrypted journal only
+/// accepts sealed files, so a plaintext file dropped into it is never taken for a real one.
+pub fn decode(vault: Option<&Vault>, name: &str, data: Vec<u8>) -> io::Result<Vec<u8>> {
+    match (is_sealed(&data), vault) {
+        (false, None) => Ok(data),
+        (false, Some(_)) => Err(io::Error::new(
+            ErrorKind::InvalidData,
+            "unencrypted file in an encrypted journal",
+        )),
+        (true, Some(vault)) => vault.open(&data, name),
+        (true, None) => Err(io::Error::new(
+            ErrorKind::PermissionDenied,
+            "journal is locked",
+        )),
+    }
+}
+
+/// Decodes file contents while `daydream encrypt` or `decrypt` runs, the only time plaintext
+/// and sealed files are expected side by side.
+pub fn decode_migrating(vault: Option<&Vault>, name: &str, data: Vec<u8>) -> io::Result<Vec<u8>> {
+    if is_sealed(&data) {
+        decode(vault, name, data)
+    } else {
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = Vault::create(dir.path(), "correct horse").unwrap();
+        assert!(exists(dir.path()));
+        assert!(Vault::create(dir.path(), "again").is_err());
+
+        let name = "2024-03-13_entry.md";
+        let sealed = vault.seal(b"dear diary", name).unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.windows(5).any(|window| window == b"diary"));
+
+        let reopened = Vault::unlock(dir.path(), "correct horse").unwrap();
+        assert_eq!(reopened.open(&sealed, name).unwrap(), b"dear diary");
+        // a sealed entry copied over another date's file does not open
+        assert!(reopened.open(&sealed, "2024-03-14_entry.md").is_err());
+        assert_eq!(
+            Vault::unlock(dir.path(), "wrong").err().unwrap().kind(),
+            ErrorKind::PermissionDenied
+        );
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(reopened.open(&tampered, name).is_err());
+        assert!(decode(None, name, sealed).is_err());
+        assert_eq!(decode(None, name, b"plain".to_vec()).unwrap(), b"plain");
+        // plaintext is refused once the journal is encrypted, except while migrating it
+        let planted = decode(Some(&reopened), name, b"planted".to_vec());
+        assert_eq!(planted.unwrap_err().kind(), ErrorKind::InvalidData);
+        let migrating = decode_migrating(Some(&reopened), name, b"plain".to_vec());
+        assert_eq!(migrating.unwrap(), b"plain");
+    }
+}
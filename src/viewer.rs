@@ -0,0 +1,302 @@
+use crate::glyphs::Glyphs;
+use crate::markdown;
+use crate::theme::Theme;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use time::Date;
+use unicode_width::UnicodeWidthChar;
+
+/// An entry shown read-only, as rendered Markdown. Nothing it does writes to disk.
+#[derive(Clone, Debug)]
+pub struct Viewer {
+    pub date: Date,
+    pub text: String,
+    /// Rows of the wrapped entry scrolled past.
+    pub scroll: u16,
+    /// Text searched for, highlighted wherever it appears.
+    pub query: String,
+    /// Whether keys are typed into the query rather than moving around.
+    pub searching: bool,
+    /// Rows holding a match, as of the last draw.
+    pub matches: Vec<u16>,
+    /// Position in `matches` of the match last jumped to.
+    pub current: usize,
+    /// Set when the next draw should scroll to the current match.
+    pub jump: bool,
+    /// Rows of text visible at the last draw, for paging.
+    pub height: u16,
+}
+
+/// What a key press did to the viewer.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ViewerEvent {
+    /// Still reading.
+    Pending,
+    /// Esc or `q` was pressed.
+    Close,
+    /// `e` was pressed, asking to edit the entry.
+    Edit,
+}
+
+impl Viewer {
+    /// Shows the text of the entry on `date`.
+    pub fn new(date: Date, text: String) -> Self {
+        Viewer {
+            date,
+            text,
+            scroll: 0,
+            query: String::new(),
+            searching: false,
+            matches: Vec::new(),
+            current: 0,
+            jump: false,
+            height: 0,
+        }
+    }
+
+    /// Applies a key press to the viewer.
+    pub fn input(&mut self, key: &KeyEvent) -> ViewerEvent {
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => {
+                    self.searching = false;
+                    self.current = 0;
+                    self.jump = true;
+                }
+                KeyCode::Esc => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyCode::Backspace => {
+                    self.query.pop();
+                }
+                KeyCode::Char(c) => self.query.push(c),
+                _ => {}
+            }
+            return ViewerEvent::Pending;
+        }
+        let page = self.height.max(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return ViewerEvent::Close,
+            KeyCode::Char('e') => return ViewerEvent::Edit,
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.query.clear();
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') if !self.matches.is_empty() => {
+                let count = self.matches.len();
+                self.current = if key.code == KeyCode::Char('n') {
+                    (self.current + 1) % count
+                } else {
+                    (self.current + count - 1) % count
+                };
+                self.jump = true;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(page),
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.scroll = self.scroll.saturating_add(page)
+            }
+            KeyCode::Home => self.scroll = 0,
+            KeyCode::End => self.scroll = u16::MAX,
+            _ => {}
+        }
+        ViewerEvent::Pending
+    }
+}
+
+/// A row of text as styled characters.
+type Row = Vec<(char, Style)>;
+
+/// Wraps styled lines to `width` columns, breaking after spaces where a line allows it.
+fn wrap(lines: &[Line], width: usize) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for line in lines {
+        let cells: Row = line
+            .spans
+            .iter()
+            .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+            .collect();
+        let mut start = 0;
+        let mut column = 0;
+        let mut last_break = None;
+        for (i, (c, _)) in cells.iter().enumerate() {
+            let char_width = c.width().unwrap_or(0);
+            if column + char_width > width && i > start {
+                let end = last_break.filter(|end| *end > start).unwrap_or(i);
+                rows.push(cells[start..end].to_vec());
+                start = end;
+                column = cells[start..i]
+                    .iter()
+                    .map(|(c, _)| c.width().unwrap_or(0))
+                    .sum();
+                last_break = None;
+            }
+            column += char_width;
+            if *c == ' ' {
+                last_break = Some(i + 1);
+            }
+        }
+        rows.push(cells[start..].to_vec());
+    }
+    rows
+}
+
+/// Patches `style` onto every case-insensitive occurrence of `query` in a row. Returns whether
+/// there was one.
+fn highlight(row: &mut Row, query: &[char], style: Style) -> bool {
+    if query.is_empty() || row.len() < query.len() {
+        return false;
+    }
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+    let mut found = false;
+    for start in 0..=row.len() - query.len() {
+        if query
+            .iter()
+            .enumerate()
+            .all(|(i, q)| same(row[start + i].0, *q))
+        {
+            for cell in &mut row[start..start + query.len()] {
+                cell.1 = cell.1.patch(style);
+            }
+            found = true;
+        }
+    }
+    found
+}
+
+/// Groups the characters of a row into spans of the same style.
+fn row_to_line(row: Row) -> Line<'static> {
+    let mut spans: Vec<Span> = Vec::new();
+    for (c, style) in row {
+        match spans.last_mut() {
+            Some(span) if span.style == style => span.content.to_mut().push(c),
+            _ => spans.push(Span::styled(c.to_string(), style)),
+        }
+    }
+    Line::from(spans)
+}
+
+/// Draws the entry, rendered as Markdown, with a status line for searching at the bottom.
+pub fn draw_viewer(
+    viewer: &mut Viewer,
+    theme: &Theme,
+    glyphs: &Glyphs,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let block = Block::default()
+        .title(format!(
+            "{}{}{} read-only",
+            glyphs.open, viewer.date, glyphs.close
+        ))
+        .border_style(theme.panel_border)
+        .borders(Borders::all())
+        .border_set(glyphs.panel_border);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(inner);
+
+    let text = markdown::render(&viewer.text, inner.width, &theme.markdown, glyphs);
+    let mut rows = wrap(&text.lines, inner.width as usize);
+    let query: Vec<char> = viewer.query.chars().collect();
+    let match_style = Style::default().add_modifier(Modifier::REVERSED);
+    viewer.matches = rows
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, row)| highlight(row, &query, match_style).then_some(i as u16))
+        .collect();
+    viewer.height = layout[0].height;
+    if viewer.jump {
+        if let Some(row) = viewer.matches.get(viewer.current) {
+            viewer.scroll = row.saturating_sub(viewer.height / 3);
+        }
+        viewer.jump = false;
+    }
+    let last = (rows.len() as u16).saturating_sub(viewer.height);
+    viewer.scroll = viewer.scroll.min(last);
+
+    let lines: Vec<Line> = rows
+        .into_iter()
+        .skip(viewer.scroll as usize)
+        .take(viewer.height as usize)
+        .map(row_to_line)
+        .collect();
+    frame.render_widget(Paragraph::new(lines), layout[0]);
+
+    let status = if viewer.searching {
+        format!("/{}", viewer.query)
+    } else if viewer.query.is_empty() {
+        "`/` search, `e` edit, `Esc` close".to_string()
+    } else if viewer.matches.is_empty() {
+        format!("`{}` not found", viewer.query)
+    } else {
+        format!(
+            "`{}` {} of {}, `n`/`N` for the next/previous",
+            viewer.query,
+            viewer.current.min(viewer.matches.len() - 1) + 1,
+            viewer.matches.len()
+        )
+    };
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().add_modifier(Modifier::DIM)),
+        layout[1],
+    );
+}
+
+/// Returns a rectangle covering most of `area`, for the viewer opened over another view.
+pub fn overlay_area(area: Rect) -> Rect {
+    Rect::new(
+        area.x + area.width / 10,
+        area.y + area.height / 10,
+        area.width - area.width / 5,
+        area.height - area.height / 5,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(rows: &[Row]) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.iter().map(|(c, _)| *c).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_and_highlight() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![
+            Span::raw("saw a "),
+            Span::styled("kingfisher", bold),
+            Span::raw(" by the river"),
+        ]);
+        let mut rows = wrap(&[line, Line::from("")], 12);
+        assert_eq!(text(&rows), ["saw a ", "kingfisher ", "by the river", ""]);
+        assert_eq!(rows[1][0].1, bold);
+
+        // words longer than the width are broken where they overflow
+        assert_eq!(
+            text(&wrap(&[Line::from("abcdefgh")], 3)),
+            ["abc", "def", "gh"]
+        );
+
+        let query: Vec<char> = "KING".chars().collect();
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+        assert!(!highlight(&mut rows[0], &query, reversed));
+        assert!(highlight(&mut rows[1], &query, reversed));
+        let line = row_to_line(rows[1].clone());
+        assert_eq!(line.spans.len(), 3);
+        assert_eq!(line.spans[0].content, "king");
+        assert_eq!(line.spans[0].style, bold.patch(reversed));
+    }
+}